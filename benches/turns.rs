@@ -0,0 +1,79 @@
+// Measures how long the monsters take to respond to one player turn as the
+// monster count grows. Run with `cargo bench --bench turns`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use terminal_horizon::game::action::{Direction, GameAction};
+use terminal_horizon::game::engine::Game;
+use terminal_horizon::game::entities::{Enemy, EntityPosition};
+use terminal_horizon::game::map::Tile;
+use terminal_horizon::game::mapgen::GENERATORS;
+
+const SEED: u64 = 42;
+const ENEMY_COUNTS: [usize; 4] = [10, 100, 300, 600];
+const TURNS: u32 = 200;
+
+// A game on a normal level, crowded with `count` goblins spread evenly over
+// the floor. The hero is made unkillable so every turn is played out.
+fn crowded_game(count: usize) -> Game {
+    let mut game = Game::new(GENERATORS[0], SEED);
+    let state = &mut game.state;
+    state.player.max_hp = u32::MAX / 2;
+    state.player.hp = state.player.max_hp;
+
+    let player = state.player.position.clone();
+    let map = &state.map;
+    let floor: Vec<EntityPosition> = (0..map.tiles.len())
+        .filter(|index| map.tiles[*index] == Tile::Floor)
+        .map(|index| map.position(index))
+        .filter(|pos| *pos != player)
+        .collect();
+    assert!(floor.len() >= count, "not enough floor for {count} enemies");
+
+    let stride = floor.len() / count;
+    state.enemies.clear();
+    state.rebuild_occupancy();
+    for pos in floor.iter().step_by(stride).take(count) {
+        state.add_enemy(Enemy::new(pos.clone(), "Goblin", "g", 20, 5, 2, 8));
+    }
+    game
+}
+
+// A direction the hero can walk back and forth in, so every step is a turn
+fn pacing_directions(game: &Game) -> [Direction; 2] {
+    let pos = &game.state.player.position;
+    let map = &game.state.map;
+    if map.is_walkable(pos.x + 1, pos.y) {
+        [Direction::Right, Direction::Left]
+    } else if pos.x > 0 && map.is_walkable(pos.x - 1, pos.y) {
+        [Direction::Left, Direction::Right]
+    } else if map.is_walkable(pos.x, pos.y + 1) {
+        [Direction::Down, Direction::Up]
+    } else {
+        [Direction::Up, Direction::Down]
+    }
+}
+
+fn bench_turns(count: usize) -> Duration {
+    let mut game = crowded_game(count);
+    let directions = pacing_directions(&game);
+    let start_turn = game.state.turn;
+
+    let start = Instant::now();
+    for step in 0..TURNS {
+        let direction = directions[step as usize % 2].clone();
+        black_box(game.step(GameAction::MovePlayer(direction)));
+    }
+    let elapsed = start.elapsed();
+
+    let turns = game.state.turn - start_turn;
+    assert!(turns > 0, "the hero never took a turn");
+    elapsed / turns
+}
+
+fn main() {
+    println!("{:>8}  {:>12}", "enemies", "per turn");
+    for count in ENEMY_COUNTS {
+        let per_turn = bench_turns(count);
+        println!("{count:>8}  {per_turn:>12.2?}");
+    }
+}
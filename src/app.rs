@@ -1,17 +1,19 @@
-use crate::errors::AppError;
-use crate::game::action_log::ActionType;
-use crate::game::ai::AiAction;
-use crate::game::entities::{Enemy, EntityPosition};
-use crate::game::player::Player;
-use crate::game::state::GameState;
-use crate::game::{action_log::ActionLog, map::GameMap};
+use crate::input::handlers::GameInput;
 use crossterm::event::{self, Event};
 use rand::{Rng, rng};
+use std::path::PathBuf;
+use terminal_horizon::errors::AppError;
+use terminal_horizon::game::action::GameAction;
+use terminal_horizon::game::engine::Game;
+use terminal_horizon::game::mapgen::{self, GENERATORS, MapGenerator};
+use terminal_horizon::replay::{self, Playback, Replay, ReplayRecorder};
+use terminal_horizon::save;
 
 pub enum AppScreen {
     MainMenu,
     Options,
     Game,
+    GameOver,
 }
 
 pub struct App {
@@ -19,39 +21,37 @@ pub struct App {
     pub should_quit: bool,
     pub menu_index: usize,
     pub menu_items: Vec<String>,
-    pub game_state: GameState,
+    pub game: Game,
+    pub options_index: usize,
+    pub generator_index: usize, // Index into mapgen::GENERATORS used for new games
+    pub seed_input: String,     // Seed typed in the options, random when empty
+    pub save_path: Option<PathBuf>, // Where the run is saved, None disables saving
+    pub has_save: bool,         // Whether "Continue" has a save to load
+    pub menu_message: Option<String>, // Shown under the main menu, e.g. why a save didn't load
+    pub replay_dir: Option<PathBuf>, // Where runs are recorded, None disables it
+    pub recorder: Option<ReplayRecorder>, // Records the current run, None when it isn't
+    pub playback: Option<Playback>, // Set while watching a replay
 }
 
-const ENEMIES_COUNT: usize = 10;
-const MAP_WIDTH: usize = 150;
-const MAP_HEIGHT: usize = 120;
+pub const OPTIONS_COUNT: usize = 2; // Map generator and seed
 
 impl App {
     pub fn new() -> Self {
-        let map = GameMap::new(MAP_WIDTH, MAP_HEIGHT);
-        let mut enemies = vec![];
-
-        let mut rand = rng();
-
-        while enemies.len() < ENEMIES_COUNT {
-            let x_pos = rand.random_range(1..MAP_WIDTH);
-            let y_pos = rand.random_range(1..MAP_HEIGHT);
-
-            if !map.is_wall(x_pos, y_pos) {
-                enemies.push(Enemy::new(
-                    EntityPosition::new(x_pos, y_pos),
-                    "Goblin",
-                    "g",
-                    20,
-                    5,
-                    2,
-                    8,
-                ));
-            }
-        }
+        let save_path = save::default_save_path();
+
+        let mut app = Self::headless(GENERATORS[0], rng().random());
+        app.screen = AppScreen::MainMenu;
+        app.has_save = save_path.as_ref().is_some_and(|path| path.exists());
+        app.save_path = save_path;
+        app.replay_dir = replay::default_replay_dir();
+        app
+    }
 
+    // A run that never touches the disk, nothing is saved or recorded. Used
+    // for watching replays and in tests.
+    pub fn headless(generator: &dyn MapGenerator, seed: u64) -> Self {
         Self {
-            screen: AppScreen::MainMenu,
+            screen: AppScreen::Game,
             should_quit: false,
             menu_index: 0,
             menu_items: vec![
@@ -60,36 +60,175 @@ impl App {
                 "Options".to_string(),
                 "Quit".to_string(),
             ],
-            game_state: GameState {
-                player: Player::new("Hero", 100, 10, 5),
-                map,
-                enemies,
-                journal: Vec::new(),
-                turn: 0,
-            },
+            game: Game::new(generator, seed),
+            options_index: 0,
+            generator_index: 0,
+            seed_input: String::new(),
+            save_path: None,
+            has_save: false,
+            menu_message: None,
+            replay_dir: None,
+            recorder: None,
+            playback: None,
+        }
+    }
+
+    // Watches a recorded run. Nothing is saved or recorded while doing so.
+    pub fn from_replay(replay: Replay) -> Result<Self, AppError> {
+        let generator = mapgen::generator_by_name(&replay.generator_name).ok_or_else(|| {
+            AppError::ReplayError(format!("unknown map generator '{}'", replay.generator_name))
+        })?;
+
+        let mut app = Self::headless(generator, replay.seed);
+        app.playback = Some(Playback::new(replay));
+        Ok(app)
+    }
+
+    fn start_new_game(&mut self) {
+        let seed = self.seed_input.parse().unwrap_or_else(|_| rng().random());
+        let generator = GENERATORS[self.generator_index];
+        self.game = Game::new(generator, seed);
+        self.game.state.run_id = rng().random();
+        self.menu_message = None;
+        self.recorder = self.start_recording(Replay::new(seed, generator.name()));
+        self.screen = AppScreen::Game;
+    }
+
+    fn save_game(&mut self) -> Result<(), AppError> {
+        if let Some(path) = &self.save_path {
+            save::write_save(path, &self.game.state)?;
+            self.has_save = true;
+        }
+        Ok(())
+    }
+
+    // A save that can't be loaded stays on disk but Continue is greyed out,
+    // and the menu says why instead of the game closing
+    fn continue_game(&mut self) {
+        let Some(path) = self.save_path.as_ref().filter(|_| self.has_save) else {
+            return;
+        };
+
+        match save::load_save(path) {
+            Ok(state) => {
+                self.game = Game::from_state(state);
+                self.menu_message = None;
+                self.screen = AppScreen::Game;
+                self.recorder = self.resume_recording();
+            }
+            Err(err) => {
+                self.has_save = false;
+                self.menu_message = Some(format!("Could not continue: {err}"));
+            }
+        }
+    }
+
+    // A dead character's run is over, so there is nothing left to continue
+    fn discard_save(&mut self) -> Result<(), AppError> {
+        if let Some(path) = &self.save_path {
+            save::delete_save(path)?;
+        }
+        self.has_save = false;
+        Ok(())
+    }
+
+    fn replay_path(&self) -> Option<PathBuf> {
+        let state = &self.game.state;
+        let dir = self.replay_dir.as_ref()?;
+        Some(replay::replay_path(dir, state.seed, state.run_id))
+    }
+
+    // Recording is a debugging aid, so failing to write it never stops play
+    fn start_recording(&mut self, replay: Replay) -> Option<ReplayRecorder> {
+        let path = self.replay_path()?;
+        ReplayRecorder::create(&path, &replay)
+            .inspect_err(|err| self.recording_failed(err))
+            .ok()
+    }
+
+    // Picks the replay of a continued run back up. Actions taken after the
+    // save (e.g. before a crash) never happened as far as the save knows, so
+    // they are dropped. Runs saved without a replay simply aren't recorded.
+    fn resume_recording(&mut self) -> Option<ReplayRecorder> {
+        let mut recording = replay::load_replay(&self.replay_path()?).ok()?;
+        let state = &self.game.state;
+        if recording.seed != state.seed || recording.generator_name != state.generator_name {
+            return None;
+        }
+
+        let turn = state.turn;
+        recording.actions.retain(|action| action.turn < turn);
+        self.start_recording(recording)
+    }
+
+    fn recording_failed(&mut self, err: &AppError) {
+        self.menu_message = Some(format!("Stopped recording the replay: {err}"));
+    }
+
+    // Applies the next recorded action when watching a replay. The run must be
+    // on the turn the action was recorded on, otherwise playback stops.
+    pub fn on_tick(&mut self) -> Result<(), AppError> {
+        if !matches!(self.screen, AppScreen::Game) {
+            return Ok(());
+        }
+        let Some(playback) = self.playback.as_mut().filter(|p| !p.is_finished()) else {
+            return Ok(());
+        };
+
+        let recorded = playback.replay.actions[playback.next].clone();
+        if recorded.turn != self.game.state.turn {
+            playback.desynced_at_turn = Some(self.game.state.turn);
+            return Ok(());
         }
+        playback.next += 1;
+
+        self.play(recorded.action)
     }
+
     pub fn handle_events(&mut self) -> Result<bool, AppError> {
         // Using hypothetical AppError
         if let Event::Key(key) = event::read().map_err(AppError::Io)? {
             match self.screen {
                 AppScreen::MainMenu => {
                     if let Some(action) = crate::input::handlers::handle_menu_input(key) {
-                        self.apply_menu_action(action); // New method needed
+                        self.apply_menu_action(action)?;
+                    }
+                }
+                AppScreen::Game if self.playback.is_some() => {
+                    // Watching a replay only accepts leaving it
+                    match crate::input::handlers::handle_game_input(key) {
+                        Some(GameInput::OpenMenu) => {
+                            self.playback = None;
+                            self.screen = AppScreen::MainMenu;
+                        }
+                        Some(GameInput::Quit) => self.should_quit = true,
+                        _ => {}
                     }
                 }
                 AppScreen::Game => {
-                    if let Some(action) = crate::input::handlers::handle_game_input(key) {
-                        self.apply_game_action(action); // New method needed
+                    if let Some(input) = crate::input::handlers::handle_game_input(key) {
+                        self.apply_game_input(input)?;
+                    }
+                }
+                AppScreen::GameOver => {
+                    if let Some(action) = crate::input::handlers::handle_game_over_input(key) {
+                        self.apply_game_over_action(action);
+                    }
+                }
+                AppScreen::Options => {
+                    if let Some(action) = crate::input::handlers::handle_options_input(key) {
+                        self.apply_options_action(action);
                     }
                 }
-                AppScreen::Options => { /* Handle options input or keep todo!() */ }
             }
         }
         Ok(self.should_quit)
     }
 
-    fn apply_menu_action(&mut self, action: crate::input::handlers::MenuAction) {
+    fn apply_menu_action(
+        &mut self,
+        action: crate::input::handlers::MenuAction,
+    ) -> Result<(), AppError> {
         use crate::input::handlers::MenuAction;
         match action {
             MenuAction::NavigateUp => {
@@ -103,118 +242,272 @@ impl App {
                 }
             }
             MenuAction::Select => match self.menu_index {
-                0 | 1 => self.screen = AppScreen::Game, // New Game or Continue
-                2 => {}                                 // Options
-                3 => self.should_quit = true,           // Quit
+                0 => self.start_new_game(),            // New Game
+                1 => self.continue_game(),             // Continue
+                2 => self.screen = AppScreen::Options, // Options
+                3 => self.should_quit = true,          // Quit
                 _ => {}
             },
             MenuAction::Quit => self.should_quit = true,
         }
+        Ok(())
     }
 
-    fn apply_game_action(&mut self, action: crate::input::handlers::GameAction) {
-        use crate::input::handlers::{Direction, GameAction};
-        let mut moved = false; // Track if player moved to update turn/log
-        let mut player_took_action = false; // turn may be passed not only by moving
-
-        let new_pos = (
-            self.game_state.player.position.x,
-            self.game_state.player.position.y,
-        );
-
+    fn apply_options_action(&mut self, action: crate::input::handlers::OptionsAction) {
+        use crate::input::handlers::OptionsAction;
         match action {
-            GameAction::OpenMenu => self.screen = AppScreen::MainMenu,
-            GameAction::Quit => self.should_quit = true,
-            GameAction::MovePlayer(dir) => {
-                match dir {
-                    Direction::Up => moved = self.game_state.player.move_up(&self.game_state.map),
-                    Direction::Down => {
-                        moved = self.game_state.player.move_down(&self.game_state.map)
-                    }
-                    Direction::Left => {
-                        moved = self.game_state.player.move_left(&self.game_state.map)
-                    }
-                    Direction::Right => {
-                        moved = self.game_state.player.move_right(&self.game_state.map)
-                    }
-                }
-                if moved {
-                    player_took_action = true;
-                    self.game_state.journal.push(ActionLog::new(
-                        self.game_state.turn,
-                        ActionType::Movement {
-                            position: EntityPosition::new(new_pos.0, new_pos.1),
-                        },
-                    ));
+            OptionsAction::NavigateUp => self.options_index = self.options_index.saturating_sub(1),
+            OptionsAction::NavigateDown => {
+                self.options_index = (self.options_index + 1).min(OPTIONS_COUNT - 1)
+            }
+            OptionsAction::NextValue if self.options_index == 0 => {
+                self.generator_index = (self.generator_index + 1) % GENERATORS.len();
+            }
+            OptionsAction::PreviousValue if self.options_index == 0 => {
+                self.generator_index =
+                    (self.generator_index + GENERATORS.len() - 1) % GENERATORS.len();
+            }
+            OptionsAction::TypeDigit(digit) if self.options_index == 1 => {
+                // Keep the typed seed within u64 range
+                let candidate = format!("{}{}", self.seed_input, digit);
+                if candidate.parse::<u64>().is_ok() {
+                    self.seed_input = candidate;
                 }
             }
+            OptionsAction::DeleteDigit if self.options_index == 1 => {
+                self.seed_input.pop();
+            }
+            OptionsAction::Back => self.screen = AppScreen::MainMenu,
+            _ => {}
+        }
+    }
+
+    fn apply_game_over_action(&mut self, action: crate::input::handlers::GameOverAction) {
+        use crate::input::handlers::GameOverAction;
+        match action {
+            GameOverAction::NewGame => self.start_new_game(),
+            GameOverAction::MainMenu => self.screen = AppScreen::MainMenu,
+            GameOverAction::Quit => self.should_quit = true,
+        }
+    }
+
+    fn apply_game_input(&mut self, input: GameInput) -> Result<(), AppError> {
+        match input {
+            GameInput::Play(action) => self.play(action)?,
+            GameInput::OpenMenu => {
+                self.save_game()?;
+                self.screen = AppScreen::MainMenu;
+            }
+            GameInput::Quit => {
+                self.save_game()?;
+                self.should_quit = true;
+            }
         }
+        Ok(())
+    }
+
+    // Records the action and lets the engine resolve it
+    fn play(&mut self, action: GameAction) -> Result<(), AppError> {
+        // Written before the step so a run that panics or gets killed still
+        // leaves a replay ending with the action that broke it
+        if let Some(recorder) = &mut self.recorder
+            && let Err(err) = recorder.record(self.game.state.turn, &action)
+        {
+            self.recorder = None;
+            self.recording_failed(&err);
+        }
+
+        self.game.step(action);
+
+        if self.game.is_over() {
+            self.screen = AppScreen::GameOver;
+            self.discard_save()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use terminal_horizon::game::action::Direction;
 
-        if player_took_action || moved {
-            self.game_state.turn += 1; // Increment turn only once after all actions resolve
-            //
-            // Store intended actions: (enemy_index, decided_action)
-            let mut enemy_actions: Vec<(usize, AiAction)> =
-                Vec::with_capacity(self.game_state.enemies.len());
+    // Scratch space for one test, so tests running in parallel don't clash
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("terminal_horizon_test_{}", std::process::id()))
+            .join(name)
+    }
 
-            // Decide Actions
-            for i in 0..self.game_state.enemies.len() {
-                let enemy_pos = self.game_state.enemies[i].position.clone(); // Clone position for decision
-                let game_state = self.game_state.clone();
+    fn recording_app(replay_dir: &Path) -> App {
+        let mut app = App::headless(GENERATORS[0], 0);
+        app.replay_dir = Some(replay_dir.to_path_buf());
+        app
+    }
 
-                let ai_decision = self.game_state.enemies[i]
-                    .ai_behavior
-                    .decide_next_action(&enemy_pos, &game_state);
+    fn play(seed: &str, replay_dir: &Path) -> App {
+        let mut app = recording_app(replay_dir);
+        app.seed_input = seed.to_string();
+        app.start_new_game();
 
-                enemy_actions.push((i, ai_decision)); // Store decision
+        for _ in 0..10 {
+            app.play(GameAction::AutoExplore).unwrap();
+            for direction in [
+                Direction::Up,
+                Direction::Right,
+                Direction::Down,
+                Direction::Left,
+            ] {
+                app.play(GameAction::MovePlayer(direction)).unwrap();
             }
+        }
 
-            // Execute Actions
-            for (enemy_index, action) in enemy_actions {
-                match action {
-                    AiAction::Wait => {
-                        // Log enemy waiting (optional)
-                    }
-                    AiAction::MoveTo(next_pos) => {
-                        // Check bounds and walls BEFORE updating position
-                        if next_pos.x < self.game_state.map.width
-                            && next_pos.y < self.game_state.map.height
-                            && !self.game_state.map.is_wall(next_pos.x, next_pos.y)
-                        {
-                            // Check for collision with player (basic)
-                            if next_pos != self.game_state.player.position {
-                                // Check for collision with other enemies (basic)
-                                let collision = self.game_state.enemies.iter().enumerate().any(
-                                    |(idx, other)| idx != enemy_index && other.position == next_pos,
-                                );
-
-                                if !collision {
-                                    self.game_state.enemies[enemy_index].position = next_pos;
-                                    // Log enemy movement (optional)
-                                }
-                            } else {
-                                // Enemy bumps into player - attack instead? Or just block.
-                                // For now, block. Combat system needed.
-                            }
-                        }
-                    }
-                    AiAction::Attack(_target_id) => {
-                        // Implement combat logic here
-                        // For now, just log (placeholder)
-
-                        self.game_state.journal.push(ActionLog::new(
-                            self.game_state.turn,
-                            ActionType::MonsterAttack {
-                                // You'll need to define this variant
-                                attacker_name: self.game_state.enemies[enemy_index].name.clone(), // Immutable borrow needed here
-                                target_name: self.game_state.player.name.clone(), // Immutable borrow needed here
-                                damage: 0, // Placeholder damage
-                            },
-                        ));
-                        // TODO: Apply damage to the player (would need mutable player borrow)
-                    }
-                }
+        app
+    }
+
+    fn recorded(app: &App) -> Replay {
+        replay::load_replay(&app.replay_path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn every_action_is_appended_to_the_replay_file() {
+        let dir = test_dir("appended");
+        let mut app = recording_app(&dir);
+        app.start_new_game();
+
+        app.play(GameAction::MovePlayer(Direction::Up)).unwrap();
+        assert_eq!(recorded(&app).actions.len(), 1);
+        app.play(GameAction::AutoExplore).unwrap();
+        assert_eq!(recorded(&app).actions.len(), 2);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn continued_run_appends_to_its_own_replay() {
+        let dir = test_dir("continued");
+        let mut app = recording_app(&dir);
+        app.save_path = Some(dir.join("save.json"));
+        app.seed_input = "7".to_string();
+        app.start_new_game();
+        app.play(GameAction::AutoExplore).unwrap();
+        app.save_game().unwrap();
+        // Lost with the crash that follows, so the save never saw it
+        app.play(GameAction::AutoExplore).unwrap();
+        let saved_run = app.replay_path().unwrap();
+
+        // A newer run on the same seed doesn't touch the saved run's replay
+        app.start_new_game();
+        app.play(GameAction::AutoExplore).unwrap();
+        assert_ne!(app.replay_path().unwrap(), saved_run);
+
+        app.continue_game();
+        assert!(app.recorder.is_some());
+        app.play(GameAction::AutoExplore).unwrap();
+
+        let replay = recorded(&app);
+        assert_eq!(app.replay_path().unwrap(), saved_run);
+        assert_eq!(replay.actions.len(), 2);
+        let mut replayed = App::from_replay(replay).unwrap();
+        replayed.on_tick().unwrap();
+        replayed.on_tick().unwrap();
+        assert_eq!(replayed.playback.unwrap().desynced_at_turn, None);
+        assert_eq!(replayed.game.state.turn, app.game.state.turn);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failing_to_record_does_not_stop_play() {
+        // A file where the replay directory should be
+        let blocked = test_dir("blocked");
+        std::fs::create_dir_all(blocked.parent().unwrap()).unwrap();
+        std::fs::write(&blocked, "").unwrap();
+        let mut app = recording_app(&blocked);
+
+        app.start_new_game();
+        app.play(GameAction::AutoExplore).unwrap();
+
+        assert!(app.recorder.is_none());
+        assert!(matches!(app.screen, AppScreen::Game));
+        assert!(app.game.state.turn > 0);
+        let message = app.menu_message.as_deref().unwrap();
+        assert!(message.contains("Stopped recording"), "{message}");
+        std::fs::remove_file(blocked).unwrap();
+    }
+
+    fn app_with_save(name: &str, contents: &str) -> App {
+        let path = test_dir(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+
+        let mut app = App::headless(GENERATORS[0], 0);
+        app.screen = AppScreen::MainMenu;
+        app.save_path = Some(path);
+        app.has_save = true;
+        app
+    }
+
+    fn select_continue(app: &mut App) {
+        use crate::input::handlers::MenuAction;
+        app.menu_index = 1;
+        app.apply_menu_action(MenuAction::Select).unwrap();
+    }
+
+    #[test]
+    fn unreadable_save_keeps_the_menu_usable() {
+        let mut app = app_with_save("corrupt.json", "not a save");
+
+        select_continue(&mut app);
+
+        assert!(matches!(app.screen, AppScreen::MainMenu));
+        assert!(!app.has_save, "Continue is greyed out");
+        assert!(app.menu_message.is_some());
+        std::fs::remove_file(app.save_path.unwrap()).unwrap();
+    }
+
+    #[test]
+    fn save_from_a_newer_game_is_explained_in_the_menu() {
+        let newer = format!(
+            r#"{{"version":{},"game_state":null}}"#,
+            save::SAVE_VERSION + 1
+        );
+        let mut app = app_with_save("newer.json", &newer);
+
+        select_continue(&mut app);
+
+        assert!(matches!(app.screen, AppScreen::MainMenu));
+        assert!(!app.has_save);
+        let message = app.menu_message.as_deref().unwrap();
+        assert!(
+            message.contains("newer than this game supports"),
+            "{message}"
+        );
+        std::fs::remove_file(app.save_path.unwrap()).unwrap();
+    }
+
+    #[test]
+    fn replay_reproduces_recorded_run() {
+        let dir = test_dir("reproduced");
+        let played = play("4321", &dir);
+
+        let mut replayed = App::from_replay(recorded(&played)).unwrap();
+        while !replayed.playback.as_ref().unwrap().is_finished() {
+            replayed.on_tick().unwrap();
+            if replayed.game.is_over() {
+                break;
             }
         }
+
+        let playback = replayed.playback.as_ref().unwrap();
+        assert_eq!(playback.desynced_at_turn, None);
+        assert_eq!(replayed.game.state.turn, played.game.state.turn);
+        assert_eq!(
+            replayed.game.state.player.position,
+            played.game.state.player.position
+        );
+        assert_eq!(replayed.game.state.player.hp, played.game.state.player.hp);
+        std::fs::remove_dir_all(dir).unwrap();
     }
 }
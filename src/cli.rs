@@ -0,0 +1,154 @@
+use std::path::PathBuf;
+use terminal_horizon::sim::OutputFormat;
+
+pub const DEFAULT_TICK_RATE_MS: u64 = 250;
+pub const DEFAULT_MAX_TURNS: u32 = 2000;
+
+// Only mean something together with --simulate
+const SIMULATION_FLAGS: [&str; 4] = ["--seed", "--generator", "--max-turns", "--format"];
+
+pub const USAGE: &str = "\
+Usage: terminal_horizon [OPTIONS]
+
+Options:
+  --replay <FILE>       Watch a recorded run instead of playing
+  --replay-speed <MS>   Milliseconds between replayed actions (default 250)
+  --simulate <GAMES>    Play GAMES games with a bot and print their statistics
+  -h, --help            Show this message
+
+Simulation options, only accepted together with --simulate:
+  --seed <SEED>         First seed of a simulation, later games count up (default 0)
+  --generator <NAME>    Map generator for a simulation (default Rooms)
+  --max-turns <TURNS>   Stop simulated games after TURNS turns (default 2000)
+  --format <csv|json>   Simulation output format (default csv)";
+
+#[derive(Debug, PartialEq)]
+pub struct CliOptions {
+    pub replay: Option<PathBuf>,
+    pub tick_rate_ms: u64,
+    pub simulate: Option<u64>, // Number of games to simulate
+    pub seed: u64,
+    pub generator: Option<String>,
+    pub max_turns: u32,
+    pub format: OutputFormat,
+    pub show_help: bool,
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<CliOptions, String> {
+    let mut options = CliOptions {
+        replay: None,
+        tick_rate_ms: DEFAULT_TICK_RATE_MS,
+        simulate: None,
+        seed: 0,
+        generator: None,
+        max_turns: DEFAULT_MAX_TURNS,
+        format: OutputFormat::Csv,
+        show_help: false,
+    };
+
+    let mut simulation_flag = None; // First one given, to name it in the error
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if SIMULATION_FLAGS.contains(&arg.as_str()) {
+            simulation_flag.get_or_insert_with(|| arg.clone());
+        }
+
+        match arg.as_str() {
+            "--replay" => {
+                let file = args.next().ok_or("--replay needs a file")?;
+                options.replay = Some(PathBuf::from(file));
+            }
+            "--replay-speed" => {
+                let speed = args.next().ok_or("--replay-speed needs a value")?;
+                options.tick_rate_ms = speed
+                    .parse()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or_else(|| format!("invalid replay speed '{}'", speed))?;
+            }
+            "--simulate" => options.simulate = Some(parse_value(&arg, args.next())?),
+            "--seed" => options.seed = parse_value(&arg, args.next())?,
+            "--generator" => {
+                options.generator = Some(args.next().ok_or("--generator needs a name")?);
+            }
+            "--max-turns" => options.max_turns = parse_value(&arg, args.next())?,
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("csv") => OutputFormat::Csv,
+                    Some("json") => OutputFormat::Json,
+                    _ => return Err("--format must be csv or json".to_string()),
+                }
+            }
+            "-h" | "--help" => options.show_help = true,
+            other => return Err(format!("unknown argument '{}'", other)),
+        }
+    }
+
+    if let Some(flag) = simulation_flag
+        && options.simulate.is_none()
+    {
+        return Err(format!("{} only applies together with --simulate", flag));
+    }
+    Ok(options)
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{}' for {}", value, flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn replay_file_and_speed_are_parsed() {
+        let options = parse(&["--replay", "run.jsonl", "--replay-speed", "40"]).unwrap();
+        assert_eq!(options.replay, Some(PathBuf::from("run.jsonl")));
+        assert_eq!(options.tick_rate_ms, 40);
+    }
+
+    #[test]
+    fn simulation_options_are_parsed() {
+        let options = parse(&[
+            "--simulate",
+            "500",
+            "--seed",
+            "42",
+            "--generator",
+            "caves",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(options.simulate, Some(500));
+        assert_eq!(options.seed, 42);
+        assert_eq!(options.generator.as_deref(), Some("caves"));
+        assert_eq!(options.max_turns, DEFAULT_MAX_TURNS);
+        assert_eq!(options.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn bad_arguments_are_rejected() {
+        assert!(parse(&["--replay"]).is_err());
+        assert!(parse(&["--replay-speed", "fast"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+        assert!(parse(&["--simulate", "-3"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn simulation_options_need_a_simulation() {
+        let error = parse(&["--replay", "run.jsonl", "--seed", "42"]).unwrap_err();
+        assert!(error.contains("--seed"), "{error}");
+        let error = parse(&["--format", "json", "--max-turns", "5"]).unwrap_err();
+        assert!(error.contains("--format"), "{error}");
+        assert!(parse(&["--generator", "caves", "--simulate", "1"]).is_ok());
+    }
+}
@@ -3,7 +3,7 @@ use thiserror::Error; // Add `thiserror` crate to Cargo.toml
 
 #[derive(Error, Debug)]
 pub enum AppError {
-    #[error("I/O Error")]
+    #[error("I/O Error: {0}")]
     Io(#[from] io::Error), // Automatically converts io::Error
 
     #[error("Event Handling Error: {0}")]
@@ -11,5 +11,14 @@ pub enum AppError {
 
     #[error("Game State Error: {0}")]
     StateError(String),
+
+    #[error("Save Error: {0}")]
+    SaveError(String),
+
+    #[error("Save file version {found} is newer than this game supports ({supported})")]
+    SaveTooNew { found: u32, supported: u32 },
+
+    #[error("Replay Error: {0}")]
+    ReplayError(String),
     // Add other error variants as needed
 }
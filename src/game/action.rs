@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+// Everything the player can do on their turn
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum GameAction {
+    MovePlayer(Direction), // Moves, or attacks an enemy in the way
+    AutoExplore,
+    Descend,
+    Ascend,
+    CloseDoor(Direction), // Closes an open door next to the player
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
@@ -1,12 +1,14 @@
 use super::entities::EntityPosition;
+use super::event::GameEvent;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ActionLog {
     pub turn: u32,
     pub action_type: ActionType,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionType {
     Movement {
         position: EntityPosition,
@@ -16,6 +18,41 @@ pub enum ActionType {
         target_name: String,
         damage: usize,
     },
+    PlayerAttack {
+        target_name: String,
+        damage: usize,
+    },
+    TerrainDamage {
+        terrain: String,
+        damage: usize,
+    },
+    EnemyKilled {
+        name: String,
+    },
+    PlayerDied {
+        killer_name: String,
+    },
+    EnemySpotted {
+        name: String,
+    },
+    ExplorationComplete,
+    DoorOpened {
+        by: String,
+        position: EntityPosition,
+    },
+    DoorClosed {
+        by: String,
+        position: EntityPosition,
+    },
+    LevelChanged {
+        depth: u32,
+    },
+    LevelUp {
+        level: u32,
+        max_hp: u32,
+        attack: u32,
+        defense: u32,
+    },
 }
 
 impl ActionLog {
@@ -26,3 +63,59 @@ impl ActionLog {
         }
     }
 }
+
+impl ActionType {
+    // The journal entry for an event, if it is worth telling the player about
+    pub fn from_event(event: &GameEvent) -> Option<Self> {
+        let action_type = match event {
+            GameEvent::PlayerMoved { to, .. } => ActionType::Movement {
+                position: to.clone(),
+            },
+            GameEvent::PlayerAttacked { target, damage } => ActionType::PlayerAttack {
+                target_name: target.clone(),
+                damage: *damage,
+            },
+            GameEvent::EnemyAttacked {
+                attacker,
+                target,
+                damage,
+            } => ActionType::MonsterAttack {
+                attacker_name: attacker.clone(),
+                target_name: target.clone(),
+                damage: *damage,
+            },
+            GameEvent::TerrainHurt { terrain, damage } => ActionType::TerrainDamage {
+                terrain: terrain.clone(),
+                damage: *damage,
+            },
+            GameEvent::EnemyKilled { name } => ActionType::EnemyKilled { name: name.clone() },
+            GameEvent::PlayerDied { killer } => ActionType::PlayerDied {
+                killer_name: killer.clone(),
+            },
+            GameEvent::EnemySpotted { name } => ActionType::EnemySpotted { name: name.clone() },
+            GameEvent::ExplorationComplete => ActionType::ExplorationComplete,
+            GameEvent::DoorOpened { by, at } => ActionType::DoorOpened {
+                by: by.clone(),
+                position: at.clone(),
+            },
+            GameEvent::DoorClosed { by, at } => ActionType::DoorClosed {
+                by: by.clone(),
+                position: at.clone(),
+            },
+            GameEvent::LevelChanged { depth } => ActionType::LevelChanged { depth: *depth },
+            GameEvent::LevelUp {
+                level,
+                max_hp,
+                attack,
+                defense,
+            } => ActionType::LevelUp {
+                level: *level,
+                max_hp: *max_hp,
+                attack: *attack,
+                defense: *defense,
+            },
+            GameEvent::TurnAdvanced { .. } | GameEvent::EnemyMoved { .. } => return None,
+        };
+        Some(action_type)
+    }
+}
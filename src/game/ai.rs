@@ -1,8 +1,19 @@
 use std::fmt::Debug;
 
-use super::entities::EntityPosition;
-use crate::game::state::GameState;
+use super::dijkstra::DijkstraMap;
+use super::entities::{Enemy, EntityPosition};
+use super::fov;
+use super::map::GameMap;
+use super::occupancy::Occupancy;
+use super::pathfinding;
+use super::player::Player;
+use super::terrain::Mobility;
+use crate::game::state::GameRng;
 use rand::Rng;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
 
 // Represents a potential action an AI can take
 #[derive(Debug, Clone, PartialEq)]
@@ -12,50 +23,176 @@ pub enum AiAction {
     Attack(usize), // Target entity index/ID (e.g., player ID 0)
 }
 
+// Read-only view of the world handed to a monster deciding its move. Other
+// monsters are only seen through the occupancy grid, so the deciding one's
+// behaviour can be borrowed mutably straight from the enemy list.
+pub struct WorldView<'a> {
+    pub map: &'a GameMap,
+    pub player: &'a Player,
+    pub occupancy: &'a Occupancy,
+    pub position: EntityPosition, // Where the deciding monster stands
+    pub mobility: Mobility,
+    pub hp: i32,
+    pub max_hp: i32,
+}
+
+impl<'a> WorldView<'a> {
+    pub fn new(
+        map: &'a GameMap,
+        player: &'a Player,
+        occupancy: &'a Occupancy,
+        actor: &Enemy,
+    ) -> Self {
+        Self {
+            map,
+            player,
+            occupancy,
+            position: actor.position.clone(),
+            mobility: actor.mobility,
+            hp: actor.hp,
+            max_hp: actor.max_hp,
+        }
+    }
+
+    // Whether another enemy stands on `pos`
+    pub fn is_enemy_at(&self, pos: &EntityPosition) -> bool {
+        *pos != self.position && self.occupancy.is_occupied(pos)
+    }
+}
+
 // Trait for any AI behavior
 pub trait AiBehavior: Debug {
     fn decide_next_action(
         &mut self,
         current_pos: &EntityPosition,
-        game_state: &GameState,
+        world: &WorldView,
+        rng: &mut GameRng,
     ) -> AiAction;
 
     fn clone_box(&self) -> Box<dyn AiBehavior>;
+
+    // Tag the behaviour is registered under in BEHAVIORS
+    fn kind(&self) -> &'static str;
+
+    // Everything the behaviour remembers, restored by its registered constructor
+    fn save_state(&self) -> Result<Value, serde_json::Error>;
+
+    fn to_saved(&self) -> Result<SavedBehavior, serde_json::Error> {
+        Ok(SavedBehavior {
+            kind: self.kind().to_string(),
+            state: self.save_state()?,
+        })
+    }
+}
+
+// Behaviour kind plus its state. This is what ends up in save files and what
+// tools can inspect without knowing the concrete behaviour types.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedBehavior {
+    pub kind: String,
+    pub state: Value,
+}
+
+type BehaviorConstructor = fn(Value) -> Result<Box<dyn AiBehavior>, serde_json::Error>;
+
+// Every behaviour that can be saved, by kind. New behaviours must be added
+// here or loading a save containing them fails.
+pub static BEHAVIORS: &[(&str, BehaviorConstructor)] = &[
+    (BasicMonsterAI::KIND, restore::<BasicMonsterAI>),
+    (CowardlyMonsterAI::KIND, restore::<CowardlyMonsterAI>),
+];
+
+fn restore<T: AiBehavior + DeserializeOwned + 'static>(
+    state: Value,
+) -> Result<Box<dyn AiBehavior>, serde_json::Error> {
+    Ok(Box::new(serde_json::from_value::<T>(state)?))
+}
+
+impl SavedBehavior {
+    pub fn into_behavior(self) -> Result<Box<dyn AiBehavior>, String> {
+        let (_, construct) = BEHAVIORS
+            .iter()
+            .find(|(kind, _)| *kind == self.kind)
+            .ok_or_else(|| format!("unknown AI behaviour '{}'", self.kind))?;
+        construct(self.state).map_err(|e| format!("invalid '{}' state: {}", self.kind, e))
+    }
+}
+
+impl Serialize for Box<dyn AiBehavior> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_saved()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn AiBehavior> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SavedBehavior::deserialize(deserializer)?
+            .into_behavior()
+            .map_err(D::Error::custom)
+    }
 }
 
 // --- Example: Simple Chasing AI ---
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AiState {
     Idle,
     Chasing,
+    Searching, // Lost sight of the player, heading to where it was last seen
 }
 
-#[derive(Debug, Clone)]
+// How long a monster keeps looking for the player before giving up
+const MAX_SEARCH_TURNS: u32 = 15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasicMonsterAI {
     pub state: AiState,
     pub target_visible: bool, // Track if player is currently visible
     pub last_known_player_pos: Option<EntityPosition>,
     pub fov_radius: i32,
+    pub search_turns: u32, // Turns spent in the Searching state so far
 }
 
 impl BasicMonsterAI {
+    pub const KIND: &'static str = "basic";
+
     pub fn new(fov_radius: i32) -> Self {
         Self {
             state: AiState::Idle,
             target_visible: false,
             last_known_player_pos: None,
             fov_radius,
+            search_turns: 0,
         }
     }
 
-    // Simple distance check (replace with proper FOV later)
-    fn is_player_in_fov(&self, self_pos: &EntityPosition, player_pos: &EntityPosition) -> bool {
-        let dx = (self_pos.x as i32 - player_pos.x as i32).abs();
-        let dy = (self_pos.y as i32 - player_pos.y as i32).abs();
-        dx <= self.fov_radius && dy <= self.fov_radius
+    fn give_up_search(&mut self) {
+        self.state = AiState::Idle;
+        self.last_known_player_pos = None;
+        self.search_turns = 0;
+    }
+
+    // Next step along an A* route that avoids walls and other monsters,
+    // falling back to a greedy step when no route is found
+    fn step_towards(
+        &self,
+        current_pos: &EntityPosition,
+        target_pos: &EntityPosition,
+        world: &WorldView,
+    ) -> EntityPosition {
+        pathfinding::next_step(
+            world.map,
+            current_pos,
+            target_pos,
+            world.mobility,
+            |pos| world.is_enemy_at(pos),
+            pathfinding::DEFAULT_SEARCH_BUDGET,
+        )
+        .unwrap_or_else(|| self.move_towards(current_pos, target_pos))
     }
 
-    // Basic pathfinding (move one step towards target)
+    // Greedy step towards target along the larger axis
     fn move_towards(
         &self,
         current_pos: &EntityPosition,
@@ -87,28 +224,43 @@ impl AiBehavior for BasicMonsterAI {
     fn clone_box(&self) -> Box<dyn AiBehavior> {
         Box::new(self.clone())
     }
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+    fn save_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
     fn decide_next_action(
         &mut self,
         current_pos: &EntityPosition,
-        game_state: &GameState,
+        world: &WorldView,
+        rng: &mut GameRng,
     ) -> AiAction {
-        let player_pos = &game_state.player.position;
+        let player_pos = &world.player.position;
 
-        self.target_visible = self.is_player_in_fov(current_pos, player_pos);
+        self.target_visible = fov::can_see(world.map, current_pos, player_pos, self.fov_radius);
 
         if self.target_visible {
             self.state = AiState::Chasing;
             self.last_known_player_pos = Some(player_pos.clone());
+            self.search_turns = 0;
+        } else if self.last_known_player_pos.is_some() {
+            // Lost track of the player, go look where it was last seen
+            self.state = AiState::Searching;
+            self.search_turns += 1;
+
+            if self.last_known_player_pos.as_ref() == Some(current_pos)
+                || self.search_turns > MAX_SEARCH_TURNS
+            {
+                self.give_up_search();
+            }
         } else {
-            // If player was just visible, maybe keep chasing for a bit? Or switch to Idle.
-            // For now, switch back to Idle immediately if not visible.
             self.state = AiState::Idle;
         }
 
         match self.state {
             AiState::Idle => {
                 // Simple random movement: 25% chance to move NSEW, 75% chance to wait
-                let mut rng = rand::rng();
                 match rng.random_range(0..5) {
                     0 => AiAction::MoveTo(EntityPosition::new(
                         current_pos.x.saturating_sub(1),
@@ -134,13 +286,176 @@ impl AiBehavior for BasicMonsterAI {
                         AiAction::Attack(0) // Assuming player ID is 0
                     } else {
                         // Move towards the player
-                        let next_pos = self.move_towards(current_pos, target_pos);
+                        let next_pos = self.step_towards(current_pos, target_pos, world);
                         AiAction::MoveTo(next_pos)
                     }
                 } else {
                     AiAction::Wait // Should not happen if Chasing state is managed correctly
                 }
             }
+            AiState::Searching => match &self.last_known_player_pos {
+                Some(target_pos) => {
+                    AiAction::MoveTo(self.step_towards(current_pos, target_pos, world))
+                }
+                None => AiAction::Wait,
+            },
+        }
+    }
+}
+
+// --- Cowardly AI: fights like BasicMonsterAI but runs away when hurt ---
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CowardlyMonsterAI {
+    pub base: BasicMonsterAI,
+    pub flee_below: f32, // Fraction of max HP under which the monster flees
+}
+
+impl CowardlyMonsterAI {
+    pub const KIND: &'static str = "cowardly";
+
+    pub fn new(fov_radius: i32, flee_below: f32) -> Self {
+        Self {
+            base: BasicMonsterAI::new(fov_radius),
+            flee_below,
+        }
+    }
+}
+
+impl AiBehavior for CowardlyMonsterAI {
+    fn clone_box(&self) -> Box<dyn AiBehavior> {
+        Box::new(self.clone())
+    }
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+    fn save_state(&self) -> Result<Value, serde_json::Error> {
+        serde_json::to_value(self)
+    }
+    fn decide_next_action(
+        &mut self,
+        current_pos: &EntityPosition,
+        world: &WorldView,
+        rng: &mut GameRng,
+    ) -> AiAction {
+        // Keep perception and memory up to date even while fleeing
+        let action = self.base.decide_next_action(current_pos, world, rng);
+
+        let is_hurt = (world.hp as f32) < world.max_hp as f32 * self.flee_below;
+
+        if is_hurt && self.base.target_visible {
+            let player_pos = &world.player.position;
+            let flee_map =
+                DijkstraMap::new(world.map, std::slice::from_ref(player_pos), world.mobility)
+                    .flee(world.map);
+
+            if let Some(step) = flee_map.best_step(current_pos, |pos| {
+                pos == player_pos || world.is_enemy_at(pos)
+            }) {
+                return AiAction::MoveTo(step);
+            }
         }
+
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn every_behaviour_round_trips_through_the_registry() {
+        let mut chasing = BasicMonsterAI::new(6);
+        chasing.state = AiState::Chasing;
+        chasing.last_known_player_pos = Some(EntityPosition::new(3, 4));
+
+        let behaviours: Vec<Box<dyn AiBehavior>> =
+            vec![Box::new(chasing), Box::new(CowardlyMonsterAI::new(8, 0.5))];
+
+        for behaviour in behaviours {
+            let saved = behaviour.to_saved().unwrap();
+            let restored = saved.clone().into_behavior().unwrap();
+
+            assert_eq!(restored.kind(), behaviour.kind());
+            assert_eq!(restored.to_saved().unwrap(), saved);
+        }
+    }
+
+    // The monster starts in the top left corner of a corridor that a wall
+    // separates from the one below, where the player hides
+    fn lose_sight_of_player(ai: &mut BasicMonsterAI, map: &GameMap) -> Player {
+        let mut player = Player::new("Hero", 100, 10, 5);
+        player.position = EntityPosition::new(5, 0);
+        let occupancy = Occupancy::new(map.width, map.height);
+        let monster = Enemy::new(EntityPosition::new(0, 0), "Goblin", "g", 20, 5, 2, 8);
+        let world = WorldView::new(map, &player, &occupancy, &monster);
+        ai.decide_next_action(&monster.position, &world, &mut GameRng::seed_from_u64(0));
+        assert_eq!(ai.state, AiState::Chasing);
+
+        player.position = EntityPosition::new(5, 2);
+        player
+    }
+
+    #[test]
+    fn lost_player_is_searched_for_where_last_seen() {
+        let map = GameMap::from_rows(&[
+            "..........", //
+            "#########.", //
+            "..........", //
+        ]);
+        let mut ai = BasicMonsterAI::new(8);
+        let player = lose_sight_of_player(&mut ai, &map);
+        let occupancy = Occupancy::new(map.width, map.height);
+        let mut monster = Enemy::new(EntityPosition::new(0, 0), "Goblin", "g", 20, 5, 2, 8);
+        let mut rng = GameRng::seed_from_u64(0);
+
+        for _ in 0..5 {
+            let world = WorldView::new(&map, &player, &occupancy, &monster);
+            let action = ai.decide_next_action(&monster.position, &world, &mut rng);
+            assert_eq!(ai.state, AiState::Searching);
+            let AiAction::MoveTo(next) = action else {
+                panic!("expected a step towards the last known position, got {action:?}");
+            };
+            monster.position = next;
+        }
+        assert_eq!(monster.position, EntityPosition::new(5, 0));
+
+        // Nobody there, so the monster gives up
+        let world = WorldView::new(&map, &player, &occupancy, &monster);
+        ai.decide_next_action(&monster.position, &world, &mut rng);
+        assert_eq!(ai.state, AiState::Idle);
+        assert_eq!(ai.last_known_player_pos, None);
+    }
+
+    #[test]
+    fn search_is_given_up_after_a_while() {
+        let map = GameMap::from_rows(&[
+            "..........", //
+            "#########.", //
+            "..........", //
+        ]);
+        let mut ai = BasicMonsterAI::new(8);
+        let player = lose_sight_of_player(&mut ai, &map);
+        let occupancy = Occupancy::new(map.width, map.height);
+        let stuck = Enemy::new(EntityPosition::new(0, 0), "Goblin", "g", 20, 5, 2, 8);
+        let world = WorldView::new(&map, &player, &occupancy, &stuck);
+        let mut rng = GameRng::seed_from_u64(0);
+
+        for _ in 0..MAX_SEARCH_TURNS {
+            ai.decide_next_action(&stuck.position, &world, &mut rng);
+            assert_eq!(ai.state, AiState::Searching);
+        }
+        ai.decide_next_action(&stuck.position, &world, &mut rng);
+        assert_eq!(ai.state, AiState::Idle);
+    }
+
+    #[test]
+    fn unknown_behaviour_kind_is_an_error() {
+        let saved = SavedBehavior {
+            kind: "telepathic".to_string(),
+            state: Value::Null,
+        };
+        assert!(saved.into_behavior().is_err());
     }
 }
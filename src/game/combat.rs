@@ -0,0 +1,107 @@
+use super::event::GameEvent;
+use super::player::Player;
+use super::state::GameState;
+use super::terrain::Hazard;
+
+// Every successful hit deals at least this much damage, so weak attackers
+// can still wear down heavily armoured targets.
+const MIN_DAMAGE: i32 = 1;
+
+pub fn calculate_damage(attack: i32, defense: i32) -> i32 {
+    (attack - defense).max(MIN_DAMAGE)
+}
+
+// Player bumps into the enemy at `enemy_index`. Removes the enemy and awards
+// exp when it dies.
+pub fn player_attacks_enemy(
+    game_state: &mut GameState,
+    enemy_index: usize,
+    events: &mut Vec<GameEvent>,
+) {
+    let damage = calculate_damage(
+        game_state.player.attack as i32,
+        game_state.enemies[enemy_index].defense,
+    );
+
+    let enemy = &mut game_state.enemies[enemy_index];
+    enemy.hp -= damage;
+    events.push(GameEvent::PlayerAttacked {
+        target: enemy.name.clone(),
+        damage: damage as usize,
+    });
+
+    if enemy.hp <= 0 {
+        let enemy = game_state.remove_enemy(enemy_index);
+        events.push(GameEvent::EnemyKilled {
+            name: enemy.name.clone(),
+        });
+
+        let exp = game_state.progression.exp_for_enemy(&enemy);
+        let player = &mut game_state.player;
+        for level in player.gain_exp(exp, &game_state.progression) {
+            events.push(GameEvent::LevelUp {
+                level,
+                max_hp: player.max_hp,
+                attack: player.attack,
+                defense: player.defense,
+            });
+        }
+    }
+}
+
+// Enemy at `enemy_index` hits the player, recording it as the cause of death
+// if the hit is fatal.
+pub fn enemy_attacks_player(
+    game_state: &mut GameState,
+    enemy_index: usize,
+    events: &mut Vec<GameEvent>,
+) {
+    let enemy = &game_state.enemies[enemy_index];
+    let damage = calculate_damage(enemy.attack, game_state.player.defense as i32);
+
+    let player = &mut game_state.player;
+    player.hp = player.hp.saturating_sub(damage as u32);
+    events.push(GameEvent::EnemyAttacked {
+        attacker: enemy.name.clone(),
+        target: player.name.clone(),
+        damage: damage as usize,
+    });
+
+    if player.hp == 0 {
+        game_state.cause_of_death = Some(format!("Slain by a {}", enemy.name));
+        events.push(GameEvent::PlayerDied {
+            killer: enemy.name.clone(),
+        });
+    }
+}
+
+// The tile under the player burns them or swallows them whole, unless they
+// are flying over it
+pub fn terrain_hurts_player(game_state: &mut GameState, events: &mut Vec<GameEvent>) {
+    let position = &game_state.player.position;
+    let tile = game_state.map.tile(position.x, position.y);
+    if !Player::MOBILITY.is_hurt_by(tile) {
+        return;
+    }
+
+    let def = tile.def();
+    let player = &mut game_state.player;
+    let (damage, death) = match def.hazard {
+        Some(Hazard::Burns(damage)) => (damage, format!("Burned to death in the {}", def.name)),
+        Some(Hazard::Fall) => (player.hp, format!("Fell into a {}", def.name)),
+        None => return,
+    };
+
+    player.hp = player.hp.saturating_sub(damage);
+    events.push(GameEvent::TerrainHurt {
+        terrain: def.name.to_string(),
+        damage: damage as usize,
+    });
+
+    if player.hp == 0 {
+        game_state.cause_of_death = Some(death);
+        events.push(GameEvent::PlayerDied {
+            killer: format!("the {}", def.name),
+        });
+    }
+}
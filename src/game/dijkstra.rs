@@ -0,0 +1,208 @@
+use super::entities::EntityPosition;
+use super::map::GameMap;
+use super::terrain::Mobility;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+// Value of tiles that no source can reach (walls included)
+pub const UNREACHABLE: i32 = i32::MAX;
+
+// Values are stored in tenths of a step so the flee coefficient keeps precision
+const STEP_COST: i32 = 10;
+
+// Flee maps scale distances by this factor (in tenths) before re-relaxing, which
+// makes fleeing entities prefer open areas over the nearest dead end
+const FLEE_COEFFICIENT: i32 = -12;
+
+// Distance field over the map with 4-way movement. Every passable tile holds
+// the cost of reaching the nearest source, slow terrain costing more; entities
+// move "downhill" by picking the neighbour with the lowest value.
+#[derive(Debug, Clone)]
+pub struct DijkstraMap {
+    pub width: usize,
+    pub height: usize,
+    pub mobility: Mobility, // Whose terrain rules the distances follow
+    values: Vec<i32>,
+}
+
+impl DijkstraMap {
+    // Multi-source distance field, e.g. towards the player or unexplored tiles,
+    // for a creature moving like `mobility`
+    pub fn new(map: &GameMap, sources: &[EntityPosition], mobility: Mobility) -> Self {
+        let mut dijkstra = Self {
+            width: map.width,
+            height: map.height,
+            mobility,
+            values: vec![UNREACHABLE; map.width * map.height],
+        };
+
+        for source in sources {
+            if map.is_passable(source.x, source.y, mobility) {
+                dijkstra.values[source.y * map.width + source.x] = 0;
+            }
+        }
+
+        dijkstra.relax(map);
+        dijkstra
+    }
+
+    // Inverted field where walking downhill means moving away from the sources
+    pub fn flee(&self, map: &GameMap) -> Self {
+        let mut flee = self.clone();
+
+        for value in flee.values.iter_mut() {
+            if *value != UNREACHABLE {
+                *value = *value * FLEE_COEFFICIENT / STEP_COST;
+            }
+        }
+
+        flee.relax(map);
+        flee
+    }
+
+    pub fn value(&self, x: usize, y: usize) -> i32 {
+        if x >= self.width || y >= self.height {
+            return UNREACHABLE;
+        }
+        self.values[y * self.width + x]
+    }
+
+    // Neighbour of `from` with the lowest value, if it improves on standing still.
+    // `is_blocked` lets callers skip tiles occupied by other entities.
+    pub fn best_step<F>(&self, from: &EntityPosition, is_blocked: F) -> Option<EntityPosition>
+    where
+        F: Fn(&EntityPosition) -> bool,
+    {
+        let mut best = None;
+        let mut best_value = self.value(from.x, from.y);
+
+        for (x, y) in neighbours(self.width, self.height, from.x, from.y) {
+            let value = self.value(x, y);
+            let position = EntityPosition::new(x, y);
+            if value < best_value && !is_blocked(&position) {
+                best_value = value;
+                best = Some(position);
+            }
+        }
+
+        best
+    }
+
+    // Propagates the current values across the map until every tile holds the
+    // cheapest cost reachable from any seeded tile
+    fn relax(&mut self, map: &GameMap) {
+        let mut open: BinaryHeap<Reverse<(i32, usize)>> = self
+            .values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| **value != UNREACHABLE)
+            .map(|(index, value)| Reverse((*value, index)))
+            .collect();
+
+        while let Some(Reverse((value, index))) = open.pop() {
+            if value > self.values[index] {
+                continue;
+            }
+
+            let (x, y) = (index % self.width, index / self.width);
+            for (nx, ny) in neighbours(self.width, self.height, x, y) {
+                if !map.is_passable(nx, ny, self.mobility) {
+                    continue;
+                }
+
+                let next_index = ny * self.width + nx;
+                let next_value = value + STEP_COST * map.movement_cost(nx, ny) as i32;
+                if next_value < self.values[next_index] {
+                    self.values[next_index] = next_value;
+                    open.push(Reverse((next_value, next_index)));
+                }
+            }
+        }
+    }
+}
+
+fn neighbours(
+    width: usize,
+    height: usize,
+    x: usize,
+    y: usize,
+) -> impl Iterator<Item = (usize, usize)> {
+    [
+        (x.checked_sub(1), Some(y)),
+        (Some(x + 1), Some(y)),
+        (Some(x), y.checked_sub(1)),
+        (Some(x), Some(y + 1)),
+    ]
+    .into_iter()
+    .filter_map(move |neighbour| match neighbour {
+        (Some(nx), Some(ny)) if nx < width && ny < height => Some((nx, ny)),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_grow_away_from_sources() {
+        let map = GameMap::from_rows(&[
+            ".....", //
+            ".###.", //
+            ".....", //
+        ]);
+        let dijkstra = DijkstraMap::new(&map, &[EntityPosition::new(0, 0)], Mobility::Walker);
+
+        assert_eq!(dijkstra.value(0, 0), 0);
+        assert_eq!(dijkstra.value(4, 0), 4 * STEP_COST);
+        assert_eq!(dijkstra.value(2, 2), 4 * STEP_COST);
+        assert_eq!(dijkstra.value(2, 1), UNREACHABLE);
+    }
+
+    #[test]
+    fn slow_and_impassable_terrain_depends_on_the_creature() {
+        let map = GameMap::from_rows(&["..~..", ".###.", "..≈.."]);
+        let walker = DijkstraMap::new(&map, &[EntityPosition::new(0, 0)], Mobility::Walker);
+        let swimmer = DijkstraMap::new(&map, &[EntityPosition::new(0, 0)], Mobility::Swimmer);
+
+        // Wading through the shallow water costs an extra step
+        assert_eq!(walker.value(4, 0), 5 * STEP_COST);
+        assert_eq!(walker.value(2, 2), UNREACHABLE);
+        assert_eq!(swimmer.value(2, 2), 4 * STEP_COST);
+    }
+
+    #[test]
+    fn best_step_moves_towards_nearest_source() {
+        let map = GameMap::from_rows(&["......."]);
+        let dijkstra = DijkstraMap::new(
+            &map,
+            &[EntityPosition::new(0, 0), EntityPosition::new(6, 0)],
+            Mobility::Walker,
+        );
+
+        assert_eq!(
+            dijkstra.best_step(&EntityPosition::new(4, 0), |_| false),
+            Some(EntityPosition::new(5, 0))
+        );
+        assert_eq!(
+            dijkstra.best_step(&EntityPosition::new(6, 0), |_| false),
+            None
+        );
+    }
+
+    #[test]
+    fn flee_map_leads_away_from_sources() {
+        let map = GameMap::from_rows(&["......."]);
+        let flee =
+            DijkstraMap::new(&map, &[EntityPosition::new(2, 0)], Mobility::Walker).flee(&map);
+
+        assert_eq!(
+            flee.best_step(&EntityPosition::new(3, 0), |_| false),
+            Some(EntityPosition::new(4, 0))
+        );
+        assert_eq!(
+            flee.best_step(&EntityPosition::new(1, 0), |_| false),
+            Some(EntityPosition::new(0, 0))
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use super::action::{Direction, GameAction};
+use super::action_log::{ActionLog, ActionType};
+use super::ai::{AiAction, WorldView};
+use super::combat;
+use super::dijkstra::DijkstraMap;
+use super::entities::EntityPosition;
+use super::event::{EventSubscriber, GameEvent};
+use super::fov::FieldOfView;
+use super::level;
+use super::map::Tile;
+use super::mapgen::MapGenerator;
+use super::occupancy::Occupancy;
+use super::player::Player;
+use super::progression::ProgressionCurve;
+use super::state::{GameRng, GameState, RunStats};
+use rand::SeedableRng;
+use std::collections::BTreeMap;
+use std::mem;
+
+const AUTO_EXPLORE_MAX_STEPS: usize = 200;
+
+// Runs the turns of a single game. Every frontend, bot and test drives the
+// game through `step`, so they all play by exactly the same rules.
+pub struct Game {
+    pub state: GameState,
+    events: Vec<GameEvent>, // Emitted so far by the step being resolved
+    subscribers: Vec<Box<dyn EventSubscriber>>,
+}
+
+impl Game {
+    pub fn new(generator: &dyn MapGenerator, seed: u64) -> Self {
+        let (level, arrival) = level::generate_level(generator, seed, 1);
+
+        let mut player = Player::new("Hero", 100, 10, 5);
+        player.position = arrival;
+
+        let mut state = GameState {
+            player,
+            fov: FieldOfView::new(level.map.width, level.map.height),
+            occupancy: Occupancy::default(),
+            map: level.map,
+            enemies: level.enemies,
+            journal: Vec::new(),
+            turn: 0,
+            stats: RunStats::default(),
+            cause_of_death: None,
+            progression: ProgressionCurve::default(),
+            seed,
+            generator_name: generator.name().to_string(),
+            run_id: 0,
+            // Single source of randomness for the whole run, so the seed and
+            // the player's inputs fully determine what happens
+            rng: GameRng::seed_from_u64(seed),
+            depth: 1,
+            levels: BTreeMap::new(),
+        };
+        state.rebuild_occupancy();
+        state.update_fov();
+        Self::from_state(state)
+    }
+
+    // Resumes a game, e.g. one loaded from a save
+    pub fn from_state(state: GameState) -> Self {
+        Self {
+            state,
+            events: Vec::new(),
+            subscribers: Vec::new(),
+        }
+    }
+
+    pub fn subscribe(&mut self, subscriber: Box<dyn EventSubscriber>) {
+        self.subscribers.push(subscriber);
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state.is_player_dead()
+    }
+
+    // Resolves the player's action and, if it took a turn, every monster's
+    // response. Returns what happened, in order.
+    pub fn step(&mut self, action: GameAction) -> Vec<GameEvent> {
+        let turn = self.state.turn;
+        if !self.is_over() {
+            self.resolve(action);
+        }
+
+        let events = mem::take(&mut self.events);
+        self.publish(turn, &events);
+        events
+    }
+
+    // Feeds a step's events to the journal, the run stats and every
+    // subscriber. `turn` is the turn the step started on.
+    fn publish(&mut self, mut turn: u32, events: &[GameEvent]) {
+        for event in events {
+            if let GameEvent::TurnAdvanced { turn: next } = event {
+                turn = *next;
+            }
+            if let Some(action_type) = ActionType::from_event(event)
+                && !self.is_hidden_from_player(event)
+            {
+                self.state.journal.push(ActionLog::new(turn, action_type));
+            }
+            self.state.stats.record(event);
+            for subscriber in &mut self.subscribers {
+                subscriber.on_event(event);
+            }
+        }
+    }
+
+    // Monsters working doors out of sight would give their position away in
+    // the journal. Checked against the view at the end of the step.
+    fn is_hidden_from_player(&self, event: &GameEvent) -> bool {
+        match event {
+            GameEvent::DoorOpened { by, at } | GameEvent::DoorClosed { by, at } => {
+                *by != self.state.player.name && !self.state.fov.is_visible(at.x, at.y)
+            }
+            _ => false,
+        }
+    }
+
+    fn resolve(&mut self, action: GameAction) {
+        let turns = match action {
+            GameAction::AutoExplore => {
+                self.auto_explore();
+                0 // Every explored step already ended its own turns
+            }
+            GameAction::Descend => {
+                let on_stairs = self.player_tile() == Tile::StairsDown;
+                if on_stairs {
+                    let depth = self.state.depth + 1;
+                    level::travel_to_depth(&mut self.state, depth, &mut self.events);
+                }
+                on_stairs as u32
+            }
+            GameAction::Ascend => {
+                let on_stairs = self.player_tile() == Tile::StairsUp && self.state.depth > 1;
+                if on_stairs {
+                    let depth = self.state.depth - 1;
+                    level::travel_to_depth(&mut self.state, depth, &mut self.events);
+                }
+                on_stairs as u32
+            }
+            GameAction::MovePlayer(dir) => self.move_player(&dir),
+            GameAction::CloseDoor(dir) => self.close_door(&dir) as u32,
+        };
+
+        self.end_player_turns(turns);
+    }
+
+    fn player_tile(&self) -> Tile {
+        let position = &self.state.player.position;
+        self.state.map.tile(position.x, position.y)
+    }
+
+    // Moves the player one tile, attacking instead when an enemy is in the
+    // way. Returns how many turns that took, slow terrain taking longer.
+    fn move_player(&mut self, dir: &Direction) -> u32 {
+        let target = position_in_direction(&self.state.player.position, dir);
+        if let Some(enemy_index) = target
+            .as_ref()
+            .and_then(|target| self.state.enemy_at(target))
+        {
+            combat::player_attacks_enemy(&mut self.state, enemy_index, &mut self.events);
+            return 1;
+        }
+        if let Some(target) = target
+            && self.open_door(&target, None)
+        {
+            return 1;
+        }
+
+        let from = self.state.player.position.clone();
+        let moved = match dir {
+            Direction::Up => self.state.player.move_up(&self.state.map),
+            Direction::Down => self.state.player.move_down(&self.state.map),
+            Direction::Left => self.state.player.move_left(&self.state.map),
+            Direction::Right => self.state.player.move_right(&self.state.map),
+        };
+        if !moved {
+            return 0;
+        }
+
+        let to = self.state.player.position.clone();
+        let turns = self.state.map.movement_cost(to.x, to.y);
+        self.events.push(GameEvent::PlayerMoved { from, to });
+        turns
+    }
+
+    // Closes the open door next to the player, unless someone stands in it.
+    // Returns whether that took a turn.
+    fn close_door(&mut self, dir: &Direction) -> bool {
+        let Some(target) = position_in_direction(&self.state.player.position, dir) else {
+            return false;
+        };
+        if self.state.enemy_at(&target).is_some() || !self.state.map.close_door(target.x, target.y)
+        {
+            return false;
+        }
+
+        self.events.push(GameEvent::DoorClosed {
+            by: self.state.player.name.clone(),
+            at: target,
+        });
+        true
+    }
+
+    // Bumping into a closed door opens it instead of moving. `enemy_index` is
+    // the monster doing the bumping, or None for the player.
+    fn open_door(&mut self, at: &EntityPosition, enemy_index: Option<usize>) -> bool {
+        if !self.state.map.open_door(at.x, at.y) {
+            return false;
+        }
+        let by = match enemy_index {
+            Some(index) => &self.state.enemies[index].name,
+            None => &self.state.player.name,
+        };
+        self.events.push(GameEvent::DoorOpened {
+            by: by.clone(),
+            at: at.clone(),
+        });
+        true
+    }
+
+    // Ends `turns` turns in a row, e.g. two after wading into shallow water
+    fn end_player_turns(&mut self, turns: u32) {
+        for _ in 0..turns {
+            self.end_player_turn();
+            if self.state.is_player_dead() {
+                break;
+            }
+        }
+    }
+
+    // Advances the turn after the player acted and lets every enemy respond
+    fn end_player_turn(&mut self) {
+        self.state.turn += 1; // Increment turn only once after all actions resolve
+        self.events.push(GameEvent::TurnAdvanced {
+            turn: self.state.turn,
+        });
+        self.state.update_fov();
+
+        combat::terrain_hurts_player(&mut self.state, &mut self.events);
+        if self.state.is_player_dead() {
+            return;
+        }
+
+        // Store intended actions: (enemy_index, decided_action)
+        let mut enemy_actions: Vec<(usize, AiAction)> =
+            Vec::with_capacity(self.state.enemies.len());
+
+        // Decide Actions
+        for i in 0..self.state.enemies.len() {
+            let enemy = &mut self.state.enemies[i];
+            if enemy.busy_turns > 0 {
+                enemy.busy_turns -= 1;
+                enemy_actions.push((i, AiAction::Wait));
+                continue;
+            }
+
+            let world = WorldView::new(
+                &self.state.map,
+                &self.state.player,
+                &self.state.occupancy,
+                enemy,
+            );
+
+            let ai_decision =
+                enemy
+                    .ai_behavior
+                    .decide_next_action(&enemy.position, &world, &mut self.state.rng);
+
+            enemy_actions.push((i, ai_decision)); // Store decision
+        }
+
+        // Execute Actions
+        for (enemy_index, action) in enemy_actions {
+            match action {
+                AiAction::Wait => {
+                    // Log enemy waiting (optional)
+                }
+                AiAction::MoveTo(next_pos) => {
+                    if self.open_door(&next_pos, Some(enemy_index)) {
+                        continue;
+                    }
+
+                    // Check bounds and terrain BEFORE updating position
+                    let map = &self.state.map;
+                    let mobility = self.state.enemies[enemy_index].mobility;
+                    if map.in_bounds(next_pos.x, next_pos.y)
+                        && mobility.can_safely_enter(map.tile(next_pos.x, next_pos.y))
+                    {
+                        // Check for collision with player (basic)
+                        if next_pos != self.state.player.position {
+                            // Check for collision with other enemies (basic)
+                            let collision = self
+                                .state
+                                .enemy_at(&next_pos)
+                                .is_some_and(|other| other != enemy_index);
+
+                            if !collision {
+                                let enemy = &self.state.enemies[enemy_index];
+                                let event = GameEvent::EnemyMoved {
+                                    name: enemy.name.clone(),
+                                    from: enemy.position.clone(),
+                                    to: next_pos.clone(),
+                                };
+                                // Slow terrain costs the mover its next turns
+                                let cost = self.state.map.movement_cost(next_pos.x, next_pos.y);
+                                self.state.enemies[enemy_index].busy_turns = cost - 1;
+                                self.state.move_enemy(enemy_index, next_pos);
+                                self.events.push(event);
+                            }
+                        } else {
+                            // Enemy bumps into player
+                            combat::enemy_attacks_player(
+                                &mut self.state,
+                                enemy_index,
+                                &mut self.events,
+                            );
+                        }
+                    }
+                }
+                AiAction::Attack(_target_id) => {
+                    combat::enemy_attacks_player(&mut self.state, enemy_index, &mut self.events);
+                }
+            }
+
+            if self.state.is_player_dead() {
+                break;
+            }
+        }
+    }
+
+    // Walks towards the nearest unexplored tile, one full turn per step, until
+    // an enemy comes into view or there is nothing left to explore
+    fn auto_explore(&mut self) {
+        for _ in 0..AUTO_EXPLORE_MAX_STEPS {
+            if let Some(enemy) = self
+                .state
+                .enemies
+                .iter()
+                .find(|e| self.state.fov.is_visible(e.position.x, e.position.y))
+            {
+                self.events.push(GameEvent::EnemySpotted {
+                    name: enemy.name.clone(),
+                });
+                return;
+            }
+
+            let unexplored = self.state.map.unexplored_tiles();
+            let explore_map = DijkstraMap::new(&self.state.map, &unexplored, Player::MOBILITY);
+            let occupancy = &self.state.occupancy;
+            let Some(step) = explore_map.best_step(&self.state.player.position, |pos| {
+                occupancy.is_occupied(pos)
+            }) else {
+                self.events.push(GameEvent::ExplorationComplete);
+                return;
+            };
+
+            let turns = if self.open_door(&step, None) {
+                1
+            } else {
+                let turns = self.state.map.movement_cost(step.x, step.y);
+                let from = mem::replace(&mut self.state.player.position, step.clone());
+                self.events.push(GameEvent::PlayerMoved { from, to: step });
+                turns
+            };
+            self.end_player_turns(turns);
+
+            if self.state.is_player_dead() {
+                return;
+            }
+        }
+    }
+}
+
+pub fn position_in_direction(position: &EntityPosition, dir: &Direction) -> Option<EntityPosition> {
+    match dir {
+        Direction::Up => Some(EntityPosition::new(position.x, position.y.checked_sub(1)?)),
+        Direction::Down => Some(EntityPosition::new(position.x, position.y + 1)),
+        Direction::Left => Some(EntityPosition::new(position.x.checked_sub(1)?, position.y)),
+        Direction::Right => Some(EntityPosition::new(position.x + 1, position.y)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::mapgen::GENERATORS;
+
+    #[test]
+    fn monsters_working_doors_out_of_sight_stay_out_of_the_journal() {
+        let mut game = Game::new(GENERATORS[0], 99);
+        let player = game.state.player.position.clone();
+        let seen = EntityPosition::new(player.x + 1, player.y);
+        let hidden = (0..game.state.map.tiles.len())
+            .map(|index| game.state.map.position(index))
+            .find(|p| !game.state.fov.is_visible(p.x, p.y))
+            .unwrap();
+        for door in [&seen, &hidden] {
+            game.state.map.set_tile(door.x, door.y, Tile::Door);
+            game.open_door(door, Some(0));
+        }
+
+        let journal_before = game.state.journal.len();
+        let events = mem::take(&mut game.events);
+        game.publish(game.state.turn, &events);
+
+        let journaled: Vec<_> = game.state.journal[journal_before..]
+            .iter()
+            .map(|entry| &entry.action_type)
+            .collect();
+        assert!(matches!(
+            journaled.as_slice(),
+            [ActionType::DoorOpened { position, .. }] if *position == seen
+        ));
+    }
+}
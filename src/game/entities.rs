@@ -1,6 +1,8 @@
 use super::ai::{AiBehavior, BasicMonsterAI};
+use super::terrain::Mobility;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EntityPosition {
     pub x: usize,
     pub y: usize,
@@ -24,7 +26,7 @@ pub enum AiState {
     Attacking,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Enemy {
     pub position: EntityPosition,
     pub symbol: String,
@@ -34,6 +36,10 @@ pub struct Enemy {
     pub attack: i32,
     pub defense: i32,
     pub ai_behavior: Box<dyn AiBehavior>,
+    #[serde(default)]
+    pub mobility: Mobility,
+    #[serde(default)]
+    pub busy_turns: u32, // Turns left before it can act again, e.g. after wading
 }
 
 impl Clone for Enemy {
@@ -47,6 +53,8 @@ impl Clone for Enemy {
             attack: self.attack,
             defense: self.defense,
             ai_behavior: self.ai_behavior.clone_box(),
+            mobility: self.mobility,
+            busy_turns: self.busy_turns,
         }
     }
 }
@@ -72,9 +80,21 @@ impl Enemy {
             attack,
             defense,
             ai_behavior: Box::new(BasicMonsterAI::new(fov_radius)),
+            mobility: Mobility::Walker,
+            busy_turns: 0,
         }
     }
 
+    pub fn with_mobility(mut self, mobility: Mobility) -> Self {
+        self.mobility = mobility;
+        self
+    }
+
+    pub fn with_behavior(mut self, ai_behavior: Box<dyn AiBehavior>) -> Self {
+        self.ai_behavior = ai_behavior;
+        self
+    }
+
     // Simple check for now, replace with proper FOV later
     // pub fn is_player_in_fov(&self, player_x: usize, player_y: usize) -> bool {
     //     let dx = (self.position.x as i32 - player_x as i32).abs();
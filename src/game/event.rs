@@ -0,0 +1,68 @@
+use super::entities::EntityPosition;
+
+// Something that happened while resolving a step, emitted by the rule that
+// made it happen. `Game::step` returns them in order; the journal and the run
+// stats are projections of this stream, and frontends can subscribe to it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    // The player's turn is over and the monsters act in `turn`
+    TurnAdvanced {
+        turn: u32,
+    },
+    PlayerMoved {
+        from: EntityPosition,
+        to: EntityPosition,
+    },
+    EnemyMoved {
+        name: String,
+        from: EntityPosition,
+        to: EntityPosition,
+    },
+    PlayerAttacked {
+        target: String,
+        damage: usize,
+    },
+    EnemyAttacked {
+        attacker: String,
+        target: String,
+        damage: usize,
+    },
+    TerrainHurt {
+        terrain: String,
+        damage: usize,
+    },
+    EnemyKilled {
+        name: String,
+    },
+    PlayerDied {
+        killer: String,
+    },
+    EnemySpotted {
+        name: String,
+    },
+    ExplorationComplete,
+    DoorOpened {
+        by: String,
+        at: EntityPosition,
+    },
+    DoorClosed {
+        by: String,
+        at: EntityPosition,
+    },
+    LevelChanged {
+        depth: u32,
+    },
+    LevelUp {
+        level: u32,
+        max_hp: u32,
+        attack: u32,
+        defense: u32,
+    },
+}
+
+// Anything outside the rules that wants to react to the game, e.g. UI
+// animations or achievements. Subscribers see each event once, after the
+// step that produced it has been fully resolved.
+pub trait EventSubscriber {
+    fn on_event(&mut self, event: &GameEvent);
+}
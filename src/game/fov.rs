@@ -0,0 +1,291 @@
+use super::entities::EntityPosition;
+use super::map::GameMap;
+
+// Multipliers transforming octant-local (dx, dy) into map coordinates
+const OCTANTS: [[i32; 4]; 8] = [
+    [1, 0, 0, 1],
+    [0, 1, 1, 0],
+    [0, -1, 1, 0],
+    [-1, 0, 0, 1],
+    [-1, 0, 0, -1],
+    [0, -1, -1, 0],
+    [0, 1, -1, 0],
+    [1, 0, 0, -1],
+];
+
+// Set of tiles visible from a single point
+#[derive(Debug, Clone, Default)]
+pub struct FieldOfView {
+    pub width: usize,
+    pub height: usize,
+    visible: Vec<bool>,
+}
+
+impl FieldOfView {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            visible: vec![false; width * height],
+        }
+    }
+
+    pub fn compute(map: &GameMap, origin: &EntityPosition, radius: i32) -> Self {
+        let mut fov = Self::new(map.width, map.height);
+        fov.recompute(map, origin, radius);
+        fov
+    }
+
+    // Reuses the existing buffer, clearing everything seen from the old origin
+    pub fn recompute(&mut self, map: &GameMap, origin: &EntityPosition, radius: i32) {
+        if self.width != map.width || self.height != map.height {
+            *self = Self::new(map.width, map.height);
+        } else {
+            self.visible.fill(false);
+        }
+
+        let width = self.width;
+        let visible = &mut self.visible;
+        compute_fov(map, origin, radius, |x, y| visible[y * width + x] = true);
+    }
+
+    pub fn is_visible(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.visible[y * self.width + x]
+    }
+}
+
+// Recursive shadowcasting. Calls `reveal` for every tile within `radius` of
+// `origin` that has an unobstructed view, including the walls that block it.
+// A tile may be revealed more than once.
+pub fn compute_fov<F: FnMut(usize, usize)>(
+    map: &GameMap,
+    origin: &EntityPosition,
+    radius: i32,
+    mut reveal: F,
+) {
+    if origin.x >= map.width || origin.y >= map.height {
+        return;
+    }
+
+    reveal(origin.x, origin.y);
+
+    for octant in OCTANTS.iter() {
+        cast_light(map, origin, radius, 1, 1.0, 0.0, octant, &mut reveal);
+    }
+}
+
+// Whether `target` is in view from `origin`, using the same shadowcasting as
+// the player's FOV so walls block sight both ways
+pub fn can_see(
+    map: &GameMap,
+    origin: &EntityPosition,
+    target: &EntityPosition,
+    radius: i32,
+) -> bool {
+    let dx = origin.x as i32 - target.x as i32;
+    let dy = origin.y as i32 - target.y as i32;
+    if dx * dx + dy * dy > radius * radius {
+        return false;
+    }
+
+    let mut seen = false;
+    compute_fov(map, origin, radius, |x, y| {
+        seen |= x == target.x && y == target.y;
+    });
+    seen
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cast_light<F: FnMut(usize, usize)>(
+    map: &GameMap,
+    origin: &EntityPosition,
+    radius: i32,
+    row: i32,
+    mut start_slope: f32,
+    end_slope: f32,
+    octant: &[i32; 4],
+    reveal: &mut F,
+) {
+    if start_slope < end_slope {
+        return;
+    }
+
+    let [xx, xy, yx, yy] = *octant;
+    let radius_squared = radius * radius;
+    let mut next_start_slope = start_slope;
+
+    for distance in row..=radius {
+        let dy = -distance;
+        let mut blocked = false;
+
+        for dx in -distance..=0 {
+            let left_slope = (dx as f32 - 0.5) / (dy as f32 + 0.5);
+            let right_slope = (dx as f32 + 0.5) / (dy as f32 - 0.5);
+
+            if start_slope < right_slope {
+                continue;
+            } else if end_slope > left_slope {
+                break;
+            }
+
+            let map_x = origin.x as i32 + dx * xx + dy * xy;
+            let map_y = origin.y as i32 + dx * yx + dy * yy;
+            let in_bounds = map_x >= 0
+                && map_y >= 0
+                && (map_x as usize) < map.width
+                && (map_y as usize) < map.height;
+
+            if in_bounds && dx * dx + dy * dy <= radius_squared {
+                reveal(map_x as usize, map_y as usize);
+            }
+
+            let opaque = !in_bounds || !map.is_transparent(map_x as usize, map_y as usize);
+
+            if blocked {
+                if opaque {
+                    next_start_slope = right_slope;
+                } else {
+                    blocked = false;
+                    start_slope = next_start_slope;
+                }
+            } else if opaque && distance < radius {
+                // Scan the lit part of the next row before this wall's shadow
+                blocked = true;
+                cast_light(
+                    map,
+                    origin,
+                    radius,
+                    distance + 1,
+                    start_slope,
+                    left_slope,
+                    octant,
+                    reveal,
+                );
+                next_start_slope = right_slope;
+            }
+        }
+
+        if blocked {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_is_always_visible() {
+        let map = GameMap::from_rows(&["...", "...", "..."]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(1, 1), 0);
+
+        assert!(fov.is_visible(1, 1));
+        assert!(!fov.is_visible(0, 0));
+    }
+
+    #[test]
+    fn open_room_is_fully_visible() {
+        let map = GameMap::from_rows(&[
+            "#######", //
+            "#.....#", //
+            "#.....#", //
+            "#.....#", //
+            "#######", //
+        ]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(3, 2), 10);
+
+        for y in 0..map.height {
+            for x in 0..map.width {
+                assert!(fov.is_visible(x, y), "({x}, {y}) should be visible");
+            }
+        }
+    }
+
+    #[test]
+    fn walls_cast_shadows() {
+        let map = GameMap::from_rows(&[
+            ".......", //
+            ".......", //
+            "...#...", //
+            ".......", //
+            ".......", //
+        ]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(3, 4), 10);
+
+        // The wall itself is seen, the tiles straight behind it are not
+        assert!(fov.is_visible(3, 2));
+        assert!(!fov.is_visible(3, 1));
+        assert!(!fov.is_visible(3, 0));
+
+        // Tiles off to the side remain in view
+        assert!(fov.is_visible(0, 0));
+        assert!(fov.is_visible(6, 0));
+    }
+
+    #[test]
+    fn cannot_see_into_closed_room() {
+        let map = GameMap::from_rows(&[
+            "..........", //
+            "..#####...", //
+            "..#...#...", //
+            "..#####...", //
+            "..........", //
+        ]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(0, 2), 20);
+
+        assert!(fov.is_visible(2, 2));
+        assert!(!fov.is_visible(3, 2));
+        assert!(!fov.is_visible(5, 2));
+        assert!(fov.is_visible(1, 0));
+    }
+
+    #[test]
+    fn radius_limits_sight() {
+        let map = GameMap::from_rows(&["..........."]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(5, 0), 3);
+
+        assert!(fov.is_visible(2, 0));
+        assert!(fov.is_visible(8, 0));
+        assert!(!fov.is_visible(1, 0));
+        assert!(!fov.is_visible(9, 0));
+    }
+
+    #[test]
+    fn corridor_around_corner_is_hidden() {
+        let map = GameMap::from_rows(&[
+            "#####", //
+            "#...#", //
+            "###.#", //
+            "###.#", //
+            "#####", //
+        ]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(1, 1), 10);
+
+        assert!(fov.is_visible(3, 1));
+        assert!(!fov.is_visible(3, 3));
+    }
+
+    #[test]
+    fn can_see_respects_walls_and_radius() {
+        let map = GameMap::from_rows(&[
+            ".....", //
+            "..#..", //
+            ".....", //
+        ]);
+        let origin = EntityPosition::new(2, 0);
+
+        assert!(can_see(&map, &origin, &EntityPosition::new(0, 2), 5));
+        assert!(!can_see(&map, &origin, &EntityPosition::new(2, 2), 5));
+        assert!(!can_see(&map, &origin, &EntityPosition::new(0, 2), 2));
+    }
+
+    #[test]
+    fn origin_on_map_edge_does_not_panic() {
+        let map = GameMap::from_rows(&["...", "..."]);
+        let fov = FieldOfView::compute(&map, &EntityPosition::new(0, 0), 5);
+
+        assert!(fov.is_visible(2, 1));
+        assert!(!fov.is_visible(3, 1));
+    }
+}
@@ -0,0 +1,384 @@
+use super::ai::CowardlyMonsterAI;
+use super::dijkstra::DijkstraMap;
+use super::entities::{Enemy, EntityPosition};
+use super::event::GameEvent;
+use super::map::{GameMap, Tile};
+use super::mapgen::{self, GENERATORS, GeneratedMap, MapGenerator, features};
+use super::pathfinding;
+use super::player::Player;
+use super::state::{GameRng, GameState};
+use super::terrain::Mobility;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::mem;
+
+pub const MAP_WIDTH: usize = 150;
+pub const MAP_HEIGHT: usize = 120;
+
+const ENEMIES_COUNT: usize = 10;
+const KOBOLD_EVERY: usize = 4;
+const BAT_EVERY: usize = 5;
+const CROCODILE_EVERY: usize = 6;
+const HAZARD_DEPTH: u32 = 3; // First floor with lava and chasms
+const MAX_SPAWN_ATTEMPTS: usize = 1000;
+const MIN_SPAWN_DISTANCE: usize = 15;
+
+// One floor of the dungeon. The floor the player is on lives directly in
+// GameState, the others are kept here so they keep their state.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Level {
+    pub depth: u32,
+    pub map: GameMap,
+    pub enemies: Vec<Enemy>,
+}
+
+// Every depth gets its own seed so a level looks the same no matter how the
+// run got there
+pub fn level_seed(run_seed: u64, depth: u32) -> u64 {
+    run_seed ^ (depth as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+// Builds a new floor, returning it with the position the player arrives at.
+// Every floor but the first has stairs up at the arrival point, and deeper
+// floors get pools of lava and chasms on top of the generator's layout.
+pub fn generate_level(
+    generator: &dyn MapGenerator,
+    run_seed: u64,
+    depth: u32,
+) -> (Level, EntityPosition) {
+    let mut rng = GameRng::seed_from_u64(level_seed(run_seed, depth));
+    let mut generated = generator.generate(MAP_WIDTH, MAP_HEIGHT, &mut rng);
+
+    if depth >= HAZARD_DEPTH {
+        let start = &generated.player_start;
+        features::add_pool(&mut generated.map, start, Tile::Lava, None, &mut rng);
+        features::add_pool(&mut generated.map, start, Tile::Chasm, None, &mut rng);
+    }
+
+    // Stairs down go as far from the arrival point as possible
+    let arrival = generated.player_start.clone();
+    let distances = DijkstraMap::new(
+        &generated.map,
+        std::slice::from_ref(&arrival),
+        Player::MOBILITY,
+    );
+    let stairs_down = mapgen::reachable_tiles(&generated.map, &arrival)
+        .into_iter()
+        .max_by_key(|p| distances.value(p.x, p.y))
+        .unwrap_or_else(|| arrival.clone());
+
+    generated
+        .map
+        .set_tile(stairs_down.x, stairs_down.y, Tile::StairsDown);
+
+    if depth > 1 {
+        generated.map.set_tile(arrival.x, arrival.y, Tile::StairsUp);
+    }
+
+    let enemies = spawn_enemies(&generated, &stairs_down, depth, &mut rng);
+
+    let level = Level {
+        depth,
+        map: generated.map,
+        enemies,
+    };
+    (level, arrival)
+}
+
+// Moves the player to another floor through the stairs. The floor being left
+// is stored so it keeps its state, new floors are generated on first visit.
+pub fn travel_to_depth(game_state: &mut GameState, depth: u32, events: &mut Vec<GameEvent>) {
+    let going_down = depth > game_state.depth;
+
+    let next = match game_state.levels.remove(&depth) {
+        Some(level) => level,
+        None => {
+            let generator =
+                mapgen::generator_by_name(&game_state.generator_name).unwrap_or(GENERATORS[0]);
+            generate_level(generator, game_state.seed, depth).0
+        }
+    };
+
+    let previous = Level {
+        depth: game_state.depth,
+        map: mem::replace(&mut game_state.map, next.map),
+        enemies: mem::replace(&mut game_state.enemies, next.enemies),
+    };
+    game_state.levels.insert(previous.depth, previous);
+    game_state.depth = depth;
+    game_state.rebuild_occupancy();
+
+    // Arrive on the matching staircase
+    let arrival_tile = if going_down {
+        Tile::StairsUp
+    } else {
+        Tile::StairsDown
+    };
+    if let Some(arrival) = game_state.map.find_tile(arrival_tile) {
+        make_room_for_player(game_state, &arrival);
+        game_state.player.position = arrival;
+    }
+
+    events.push(GameEvent::LevelChanged { depth });
+    game_state.update_fov();
+}
+
+// Shoves an enemy standing on the arrival tile to the nearest free tile it
+// can safely stand on, so the player never ends up on top of it
+fn make_room_for_player(game_state: &mut GameState, arrival: &EntityPosition) {
+    let Some(index) = game_state.enemy_at(arrival) else {
+        return;
+    };
+    let mobility = game_state.enemies[index].mobility;
+    let map = &game_state.map;
+
+    let mut visited = vec![false; map.width * map.height];
+    visited[map.index(arrival.x, arrival.y)] = true;
+    let mut open = VecDeque::from([arrival.clone()]);
+
+    while let Some(position) = open.pop_front() {
+        if position != *arrival && game_state.enemy_at(&position).is_none() {
+            game_state.move_enemy(index, position);
+            return;
+        }
+
+        for (dx, dy) in [(0, -1), (-1, 0), (1, 0), (0, 1)] {
+            let (Some(x), Some(y)) = (
+                position.x.checked_add_signed(dx),
+                position.y.checked_add_signed(dy),
+            ) else {
+                continue;
+            };
+            if map.in_bounds(x, y)
+                && !visited[map.index(x, y)]
+                && mobility.can_safely_enter(map.tile(x, y))
+            {
+                visited[map.index(x, y)] = true;
+                open.push_back(EntityPosition::new(x, y));
+            }
+        }
+    }
+
+    // Boxed in, so anywhere on the floor it can stand will do. Only a floor
+    // with no room left at all loses the enemy.
+    let elsewhere = (0..map.tiles.len()).map(|i| map.position(i)).find(|p| {
+        p != arrival
+            && mobility.can_safely_enter(map.tile(p.x, p.y))
+            && game_state.enemy_at(p).is_none()
+    });
+    match elsewhere {
+        Some(position) => game_state.move_enemy(index, position),
+        None => {
+            game_state.remove_enemy(index);
+        }
+    }
+}
+
+// Places enemies on tiles reachable from the player start. With rooms the
+// player gets the first room to themselves and enemies are spread across
+// the rest, otherwise they keep some distance from the player.
+fn spawn_enemies(
+    generated: &GeneratedMap,
+    stairs_down: &EntityPosition,
+    depth: u32,
+    rng: &mut GameRng,
+) -> Vec<Enemy> {
+    let player_start = &generated.player_start;
+    let reachable = mapgen::reachable_tiles(&generated.map, player_start);
+
+    let spawn_groups: Vec<Vec<EntityPosition>> = match generated.rooms.split_first() {
+        Some((_, rest)) if !rest.is_empty() => rest
+            .iter()
+            .map(|room| {
+                reachable
+                    .iter()
+                    .filter(|p| room.contains(p))
+                    .cloned()
+                    .collect()
+            })
+            .collect(),
+        _ => vec![
+            reachable
+                .iter()
+                .filter(|p| pathfinding::manhattan_distance(p, player_start) >= MIN_SPAWN_DISTANCE)
+                .cloned()
+                .collect(),
+        ],
+    };
+    let spawn_groups: Vec<Vec<EntityPosition>> = spawn_groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .collect();
+    // Crocodiles only spawn in or next to water
+    let waterside: Vec<EntityPosition> = spawn_groups
+        .iter()
+        .flatten()
+        .filter(|p| is_by_water(&generated.map, p))
+        .cloned()
+        .collect();
+
+    let mut enemies: Vec<Enemy> = vec![];
+    let mut attempts = 0;
+    while enemies.len() < ENEMIES_COUNT && !spawn_groups.is_empty() && attempts < MAX_SPAWN_ATTEMPTS
+    {
+        attempts += 1;
+        // Every few spawns is a kobold that runs away when hurt, a bat that
+        // flies over water and chasms or a crocodile that swims. Levels
+        // without lakes or rivers get a goblin instead of the crocodile.
+        let slot = enemies.len();
+        let is_kobold = slot % KOBOLD_EVERY == KOBOLD_EVERY - 1;
+        let is_bat = !is_kobold && slot % BAT_EVERY == BAT_EVERY - 1;
+        let is_crocodile = !is_kobold
+            && !is_bat
+            && slot % CROCODILE_EVERY == CROCODILE_EVERY - 1
+            && !waterside.is_empty();
+
+        let candidates = if is_crocodile {
+            &waterside
+        } else {
+            &spawn_groups[slot % spawn_groups.len()]
+        };
+        let position = candidates[rng.random_range(0..candidates.len())].clone();
+
+        if position == *player_start
+            || position == *stairs_down
+            || enemies.iter().any(|e| e.position == position)
+        {
+            continue;
+        }
+
+        let enemy = if is_kobold {
+            Enemy::new(position, "Kobold", "k", 12, 4, 1, 8)
+                .with_behavior(Box::new(CowardlyMonsterAI::new(8, 0.5)))
+        } else if is_bat {
+            Enemy::new(position, "Bat", "b", 8, 3, 0, 8).with_mobility(Mobility::Flyer)
+        } else if is_crocodile {
+            Enemy::new(position, "Crocodile", "c", 24, 6, 3, 6).with_mobility(Mobility::Swimmer)
+        } else {
+            Enemy::new(position, "Goblin", "g", 20, 5, 2, 8)
+        };
+        enemies.push(scale_for_depth(enemy, depth));
+    }
+
+    enemies
+}
+
+fn is_by_water(map: &GameMap, position: &EntityPosition) -> bool {
+    (position.y.saturating_sub(1)..=position.y + 1).any(|y| {
+        (position.x.saturating_sub(1)..=position.x + 1).any(|x| {
+            map.in_bounds(x, y) && matches!(map.tile(x, y), Tile::Water | Tile::ShallowWater)
+        })
+    })
+}
+
+// Deeper floors hold tougher versions of the same monsters
+fn scale_for_depth(mut enemy: Enemy, depth: u32) -> Enemy {
+    let bonus = depth.saturating_sub(1) as i32;
+    enemy.max_hp += enemy.max_hp * bonus / 4;
+    enemy.hp = enemy.max_hp;
+    enemy.attack += bonus;
+    enemy.defense += bonus / 2;
+    enemy
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::engine::Game;
+
+    #[test]
+    fn deeper_levels_have_both_staircases() {
+        for generator in GENERATORS {
+            let (level, arrival) = generate_level(*generator, 7, 2);
+
+            assert_eq!(level.map.find_tile(Tile::StairsUp), Some(arrival));
+            assert!(level.map.find_tile(Tile::StairsDown).is_some());
+        }
+    }
+
+    #[test]
+    fn enemies_on_the_stairs_are_pushed_aside_not_removed() {
+        let mut game = Game::new(GENERATORS[0], 7);
+        let state = &mut game.state;
+        let arrival = state.player.position.clone();
+        state.enemies.clear();
+        state.rebuild_occupancy();
+
+        // One enemy on the arrival tile and one on each of its neighbours
+        let crowd = [(0, 0), (0, -1), (-1, 0), (1, 0), (0, 1)];
+        for (dx, dy) in crowd {
+            let x = arrival.x.checked_add_signed(dx).unwrap();
+            let y = arrival.y.checked_add_signed(dy).unwrap();
+            state.map.set_tile(x, y, Tile::Floor);
+            let goblin = Enemy::new(EntityPosition::new(x, y), "Goblin", "g", 20, 5, 2, 8);
+            state.add_enemy(goblin);
+        }
+
+        make_room_for_player(state, &arrival);
+
+        assert_eq!(state.enemies.len(), crowd.len());
+        assert_eq!(state.enemy_at(&arrival), None);
+        let moved = &state.enemies[0].position;
+        assert_eq!(pathfinding::manhattan_distance(moved, &arrival), 2);
+        assert!(state.map.is_walkable(moved.x, moved.y));
+    }
+
+    #[test]
+    fn boxed_in_enemies_on_the_stairs_are_moved_elsewhere() {
+        let mut game = Game::new(GENERATORS[0], 7);
+        let state = &mut game.state;
+        let arrival = state.player.position.clone();
+        for (x, y) in [
+            (arrival.x, arrival.y - 1),
+            (arrival.x - 1, arrival.y),
+            (arrival.x + 1, arrival.y),
+            (arrival.x, arrival.y + 1),
+        ] {
+            state.map.set_tile(x, y, Tile::Wall);
+        }
+        state.enemies.clear();
+        state.rebuild_occupancy();
+        state.add_enemy(Enemy::new(arrival.clone(), "Goblin", "g", 20, 5, 2, 8));
+
+        make_room_for_player(state, &arrival);
+
+        assert_eq!(state.enemies.len(), 1);
+        assert_eq!(state.enemy_at(&arrival), None);
+        let moved = &state.enemies[0].position;
+        assert!(state.map.is_walkable(moved.x, moved.y));
+    }
+
+    #[test]
+    fn crocodiles_only_spawn_by_the_water() {
+        let mut crocodiles = 0;
+        for generator in GENERATORS {
+            for seed in 0..5 {
+                let (level, _) = generate_level(*generator, seed, 1);
+                let has_water = level
+                    .map
+                    .tiles
+                    .iter()
+                    .any(|tile| matches!(tile, Tile::Water | Tile::ShallowWater));
+
+                for enemy in level.enemies.iter().filter(|e| e.name == "Crocodile") {
+                    assert!(has_water, "{} seed {seed}", generator.name());
+                    assert!(is_by_water(&level.map, &enemy.position));
+                    crocodiles += 1;
+                }
+            }
+        }
+        assert!(crocodiles > 0);
+    }
+
+    #[test]
+    fn enemies_get_stronger_with_depth() {
+        let goblin = || Enemy::new(EntityPosition::new(0, 0), "Goblin", "g", 20, 5, 2, 8);
+        let shallow = scale_for_depth(goblin(), 1);
+        let deep = scale_for_depth(goblin(), 5);
+
+        assert_eq!(shallow.max_hp, 20);
+        assert!(deep.max_hp > shallow.max_hp);
+        assert!(deep.attack > shallow.attack);
+    }
+}
@@ -1,64 +1,396 @@
-use rand::{Rng, rng};
+use super::entities::EntityPosition;
+use super::fov::FieldOfView;
+use super::player::Player;
+use super::terrain::{Hazard, Mobility};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Tile {
     Floor,
     Wall,
-    Door,
-    Water,
+    Door, // Closed, opens when bumped
+    OpenDoor,
+    Water, // Deep, only swimmers and flyers get across
+    StairsDown,
+    StairsUp,
+    ShallowWater,
+    Lava,
+    Chasm,
 }
 
-#[derive(Clone)]
+// Colours tiles are drawn with. The frontend decides what they look like.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TileColor {
+    DarkGray,
+    White,
+    LightYellow,
+    Blue,
+    LightCyan,
+    Cyan,
+    Red,
+}
+
+// Everything the game and the frontends need to know about a kind of tile
+#[derive(Debug)]
+pub struct TileDef {
+    pub tile: Tile,
+    pub name: &'static str,
+    pub glyph: &'static str,
+    pub color: TileColor,
+    pub bold: bool,
+    // On foot, see `Mobility` for other creatures
+    pub walkable: bool,
+    // Swimmers and flyers can enter it even when it isn't walkable
+    pub swimmable: bool,
+    pub flyable: bool,
+    pub transparent: bool,
+    // Harms anyone on the tile who isn't flying
+    pub hazard: Option<Hazard>,
+    // Turns it takes to step onto the tile
+    pub movement_cost: u32,
+    // What bumping into or closing the tile turns it into
+    pub opens_into: Option<Tile>,
+    pub closes_into: Option<Tile>,
+}
+
+// One entry per tile, in the order of the `Tile` variants
+pub static TILE_DEFS: [TileDef; 10] = [
+    TileDef {
+        tile: Tile::Floor,
+        name: "floor",
+        glyph: ".",
+        color: TileColor::DarkGray,
+        bold: false,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::Wall,
+        name: "wall",
+        glyph: "#",
+        color: TileColor::White,
+        bold: false,
+        walkable: false,
+        swimmable: false,
+        flyable: false,
+        transparent: false,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::Door,
+        name: "door",
+        glyph: "+",
+        color: TileColor::LightYellow,
+        bold: false,
+        walkable: false,
+        swimmable: false,
+        flyable: false,
+        transparent: false,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: Some(Tile::OpenDoor),
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::OpenDoor,
+        name: "open door",
+        glyph: "'",
+        color: TileColor::LightYellow,
+        bold: false,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: Some(Tile::Door),
+    },
+    TileDef {
+        tile: Tile::Water,
+        name: "deep water",
+        glyph: "≈",
+        color: TileColor::Blue,
+        bold: false,
+        walkable: false,
+        swimmable: true,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::StairsDown,
+        name: "stairs down",
+        glyph: ">",
+        color: TileColor::LightCyan,
+        bold: true,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::StairsUp,
+        name: "stairs up",
+        glyph: "<",
+        color: TileColor::LightCyan,
+        bold: true,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::ShallowWater,
+        name: "shallow water",
+        glyph: "~",
+        color: TileColor::Cyan,
+        bold: false,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: None,
+        movement_cost: 2,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::Lava,
+        name: "lava",
+        glyph: "≋",
+        color: TileColor::Red,
+        bold: true,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: Some(Hazard::Burns(25)),
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+    TileDef {
+        tile: Tile::Chasm,
+        name: "chasm",
+        glyph: ":",
+        color: TileColor::DarkGray,
+        bold: true,
+        walkable: true,
+        swimmable: false,
+        flyable: true,
+        transparent: true,
+        hazard: Some(Hazard::Fall),
+        movement_cost: 1,
+        opens_into: None,
+        closes_into: None,
+    },
+];
+
+impl Tile {
+    pub fn def(self) -> &'static TileDef {
+        &TILE_DEFS[self as usize]
+    }
+}
+
+// Tiles are stored row by row in a single Vec, `index` maps (x, y) into it
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameMap {
     pub width: usize,
     pub height: usize,
-    pub tiles: Vec<Vec<Tile>>,
+    pub tiles: Vec<Tile>,
+    pub explored: Vec<bool>, // Tiles the player has seen at least once
 }
 
 impl GameMap {
-    pub fn new(width: usize, height: usize) -> Self {
-        let mut rng = rng();
-        let mut tiles = vec![vec![Tile::Floor; width]; height];
-
-        // Add some random walls
-        for tile_row in tiles.iter_mut() {
-            for tile in tile_row.iter_mut() {
-                if rng.random_ratio(1, 10) {
-                    *tile = Tile::Wall;
-                }
-            }
-        }
-        // Add borders
-        for x in 0..width {
-            tiles[0][x] = Tile::Wall;
-            tiles[height - 1][x] = Tile::Wall;
-        }
-
-        for item in tiles.iter_mut().take(height) {
-            item[0] = Tile::Wall;
-            item[width - 1] = Tile::Wall;
-        }
+    // Builds a map from rows of tiles, as the generators produce them
+    pub fn from_tiles(rows: Vec<Vec<Tile>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
 
         Self {
             width,
             height,
-            tiles,
+            explored: vec![false; width * height],
+            tiles: rows.into_iter().flatten().collect(),
         }
     }
 
-    pub fn is_wall(&self, x: usize, y: usize) -> bool {
-        if x >= self.width || y >= self.height {
-            return true;
+    // Builds a map from ASCII rows using the glyphs of the tile table
+    #[cfg(test)]
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let tiles = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .map(|c| {
+                        TILE_DEFS
+                            .iter()
+                            .find(|def| def.glyph.starts_with(c))
+                            .map_or(Tile::Floor, |def| def.tile)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self::from_tiles(tiles)
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    // Past the right edge would silently wrap into the next row
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        debug_assert!(self.in_bounds(x, y), "({x}, {y}) is outside the map");
+        y * self.width + x
+    }
+
+    pub fn position(&self, index: usize) -> EntityPosition {
+        EntityPosition::new(index % self.width, index / self.width)
+    }
+
+    pub fn tile(&self, x: usize, y: usize) -> Tile {
+        self.tiles[self.index(x, y)]
+    }
+
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: Tile) {
+        let index = self.index(x, y);
+        self.tiles[index] = tile;
+    }
+
+    // Anything outside the map can't be entered
+    pub fn is_walkable(&self, x: usize, y: usize) -> bool {
+        self.in_bounds(x, y) && self.tile(x, y).def().walkable
+    }
+
+    // Whether routes planned for a creature moving like `mobility` can lead
+    // through the tile. Actually stepping in is checked by the mover.
+    pub fn is_passable(&self, x: usize, y: usize, mobility: Mobility) -> bool {
+        self.in_bounds(x, y) && mobility.can_path_through(self.tile(x, y))
+    }
+
+    // Opens the door at (x, y), returning whether there was one to open
+    pub fn open_door(&mut self, x: usize, y: usize) -> bool {
+        self.transform(x, y, |def| def.opens_into)
+    }
+
+    pub fn close_door(&mut self, x: usize, y: usize) -> bool {
+        self.transform(x, y, |def| def.closes_into)
+    }
+
+    fn transform(&mut self, x: usize, y: usize, into: fn(&TileDef) -> Option<Tile>) -> bool {
+        let Some(tile) = self
+            .in_bounds(x, y)
+            .then(|| into(self.tile(x, y).def()))
+            .flatten()
+        else {
+            return false;
+        };
+        self.set_tile(x, y, tile);
+        true
+    }
+
+    // Anything outside the map blocks sight
+    pub fn is_transparent(&self, x: usize, y: usize) -> bool {
+        self.in_bounds(x, y) && self.tile(x, y).def().transparent
+    }
+
+    pub fn movement_cost(&self, x: usize, y: usize) -> u32 {
+        self.tile(x, y).def().movement_cost
+    }
+
+    pub fn find_tile(&self, wanted: Tile) -> Option<EntityPosition> {
+        self.tiles
+            .iter()
+            .position(|tile| *tile == wanted)
+            .map(|index| self.position(index))
+    }
+
+    pub fn is_explored(&self, x: usize, y: usize) -> bool {
+        self.in_bounds(x, y) && self.explored[self.index(x, y)]
+    }
+
+    // Remembers everything currently in view
+    pub fn mark_explored(&mut self, fov: &FieldOfView) {
+        let width = self.width;
+        for (index, explored) in self.explored.iter_mut().enumerate() {
+            *explored |= fov.is_visible(index % width, index / width);
         }
-        matches!(self.tiles[y][x], Tile::Wall)
+    }
+
+    // Tiles the player has not seen yet and could walk to
+    pub fn unexplored_tiles(&self) -> Vec<EntityPosition> {
+        (0..self.tiles.len())
+            .filter(|index| {
+                !self.explored[*index] && Player::MOBILITY.can_path_through(self.tiles[*index])
+            })
+            .map(|index| self.position(index))
+            .collect()
     }
 
     pub fn get_tile_symbol(&self, x: usize, y: usize) -> &str {
-        match self.tiles[y][x] {
-            Tile::Floor => ".",
-            Tile::Wall => "#",
-            Tile::Door => "+",
-            Tile::Water => "~",
+        self.tile(x, y).def().glyph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_table_is_in_variant_order() {
+        for (index, def) in TILE_DEFS.iter().enumerate() {
+            assert_eq!(def.tile as usize, index, "{:?}", def.tile);
         }
     }
+
+    #[test]
+    fn from_rows_reads_the_tile_glyphs() {
+        let map = GameMap::from_rows(&["#.+'", "~<>≈"]);
+
+        assert_eq!(map.tile(2, 0), Tile::Door);
+        assert_eq!(map.tile(0, 1), Tile::ShallowWater);
+        assert_eq!(map.tile(3, 1), Tile::Water);
+        assert_eq!(map.get_tile_symbol(2, 1), ">");
+        assert_eq!(map.position(map.index(1, 1)), EntityPosition::new(1, 1));
+        assert!(!map.is_walkable(0, 0) && map.is_walkable(1, 0));
+        assert!(!map.is_walkable(4, 0), "outside the map");
+    }
+
+    #[test]
+    fn doors_open_and_close() {
+        let mut map = GameMap::from_rows(&["#+#"]);
+        assert!(!map.is_walkable(1, 0) && !map.is_transparent(1, 0));
+        assert!(map.is_passable(1, 0, Mobility::Walker));
+        assert!(!map.close_door(1, 0), "already closed");
+
+        assert!(map.open_door(1, 0));
+        assert_eq!(map.tile(1, 0), Tile::OpenDoor);
+        assert!(map.is_walkable(1, 0) && map.is_transparent(1, 0));
+
+        assert!(map.close_door(1, 0));
+        assert_eq!(map.tile(1, 0), Tile::Door);
+        assert!(!map.open_door(0, 0), "walls don't open");
+    }
 }
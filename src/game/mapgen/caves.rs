@@ -0,0 +1,114 @@
+use super::{GeneratedMap, features, find_regions, position_near_center};
+use crate::game::entities::EntityPosition;
+use crate::game::map::{GameMap, Tile};
+use crate::game::state::GameRng;
+use rand::Rng;
+
+const INITIAL_WALL_CHANCE: f64 = 0.45;
+const SMOOTHING_PASSES: usize = 6;
+const WALL_THRESHOLD: usize = 5; // Walls among the 8 neighbours to become a wall
+const OPEN_SPACE_PASSES: usize = 3; // Early passes also break up wide open areas
+const OPEN_SPACE_THRESHOLD: usize = 2; // Max walls within 2 tiles to count as open
+const LAKES: usize = 4;
+
+// Organic caves: random noise smoothed by a cellular automaton. Only the
+// largest open region is kept so every floor tile is reachable from the start,
+// and underground lakes are flooded in where they don't block the way.
+pub fn generate(width: usize, height: usize, rng: &mut GameRng) -> GeneratedMap {
+    let mut tiles = vec![vec![Tile::Wall; width]; height];
+
+    for (y, row) in tiles.iter_mut().enumerate() {
+        for (x, tile) in row.iter_mut().enumerate() {
+            let is_border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+            if !is_border && !rng.random_bool(INITIAL_WALL_CHANCE) {
+                *tile = Tile::Floor;
+            }
+        }
+    }
+
+    for pass in 0..SMOOTHING_PASSES {
+        tiles = smooth(&tiles, pass < OPEN_SPACE_PASSES);
+    }
+
+    let mut map = GameMap::from_tiles(tiles);
+
+    // Seal every pocket that isn't part of the largest cave
+    let mut regions = find_regions(&map);
+    regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+    for pocket in regions.iter().skip(1) {
+        for position in pocket {
+            map.set_tile(position.x, position.y, Tile::Wall);
+        }
+    }
+
+    let player_start = regions
+        .first()
+        .map(|cave| position_near_center(cave, width, height))
+        .unwrap_or_else(|| EntityPosition::new(width / 2, height / 2));
+
+    features::add_lakes(&mut map, &player_start, LAKES, rng);
+
+    GeneratedMap {
+        map,
+        rooms: Vec::new(),
+        player_start,
+    }
+}
+
+fn smooth(tiles: &[Vec<Tile>], fill_open_space: bool) -> Vec<Vec<Tile>> {
+    let height = tiles.len();
+    let width = tiles[0].len();
+    let mut next = tiles.to_vec();
+
+    // Borders are left untouched so the map stays sealed
+    for (y, row) in next.iter_mut().enumerate().take(height - 1).skip(1) {
+        for (x, tile) in row.iter_mut().enumerate().take(width - 1).skip(1) {
+            let walls = count_walls(tiles, x, y, 1);
+            if walls >= WALL_THRESHOLD
+                || (fill_open_space && count_walls(tiles, x, y, 2) <= OPEN_SPACE_THRESHOLD)
+            {
+                *tile = Tile::Wall;
+            } else if walls < WALL_THRESHOLD - 1 {
+                *tile = Tile::Floor;
+            }
+        }
+    }
+
+    next
+}
+
+// Walls within `radius` of (x, y), excluding the tile itself. Tiles past the
+// map edge count as walls.
+fn count_walls(tiles: &[Vec<Tile>], x: usize, y: usize, radius: usize) -> usize {
+    let mut walls = 0;
+    for ny in y as isize - radius as isize..=(y + radius) as isize {
+        for nx in x as isize - radius as isize..=(x + radius) as isize {
+            if (nx, ny) == (x as isize, y as isize) {
+                continue;
+            }
+            let is_wall = tiles
+                .get(ny as usize)
+                .and_then(|row| row.get(nx as usize))
+                .is_none_or(|tile| matches!(tile, Tile::Wall));
+            walls += is_wall as usize;
+        }
+    }
+    walls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn only_one_cave_is_left() {
+        for seed in 0..10 {
+            let generated = generate(80, 50, &mut GameRng::seed_from_u64(seed));
+            let start = &generated.player_start;
+
+            assert!(generated.map.is_walkable(start.x, start.y));
+            assert_eq!(find_regions(&generated.map).len(), 1);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use super::reachable_tiles;
+use crate::game::entities::EntityPosition;
+use crate::game::map::{GameMap, Tile};
+use crate::game::state::GameRng;
+use rand::Rng;
+
+const POOL_MIN_RADIUS: usize = 2;
+const POOL_MAX_RADIUS: usize = 5;
+const POOL_PLACEMENT_ATTEMPTS: usize = 20;
+const RIVER_WIDTH: usize = 2;
+
+// Floods the floor around a random spot with a roughly round pool of `core`
+// tiles, fringed with `rim` when given. Pools that would cut any part of the
+// map off from `start` on foot are undone. Returns whether one was placed.
+pub fn add_pool(
+    map: &mut GameMap,
+    start: &EntityPosition,
+    core: Tile,
+    rim: Option<Tile>,
+    rng: &mut GameRng,
+) -> bool {
+    let reachable_before = reachable_mask(map, start);
+
+    for _ in 0..POOL_PLACEMENT_ATTEMPTS {
+        let radius = rng.random_range(POOL_MIN_RADIUS..=POOL_MAX_RADIUS);
+        let center = EntityPosition::new(
+            rng.random_range(0..map.width),
+            rng.random_range(0..map.height),
+        );
+        let too_close = center.x.abs_diff(start.x) + center.y.abs_diff(start.y) <= radius * 2;
+        if too_close || map.tile(center.x, center.y) != Tile::Floor {
+            continue;
+        }
+
+        let mut changed = Vec::new();
+        for y in center.y.saturating_sub(radius)..=(center.y + radius).min(map.height - 1) {
+            for x in center.x.saturating_sub(radius)..=(center.x + radius).min(map.width - 1) {
+                if map.tile(x, y) != Tile::Floor {
+                    continue;
+                }
+
+                // Ragged edges so pools don't all look like circles
+                let distance = x.abs_diff(center.x).pow(2) + y.abs_diff(center.y).pow(2);
+                let edge = radius * radius + rng.random_range(0..=radius);
+                let tile = if distance * 2 <= edge {
+                    core
+                } else if distance <= edge {
+                    rim.unwrap_or(Tile::Floor)
+                } else {
+                    continue;
+                };
+
+                changed.push((x, y));
+                map.set_tile(x, y, tile);
+            }
+        }
+
+        let reachable_after = reachable_mask(map, start);
+        let cut_off = (0..map.tiles.len()).any(|index| {
+            reachable_before[index] && map.tiles[index] != core && !reachable_after[index]
+        });
+        if !cut_off {
+            return true;
+        }
+
+        for (x, y) in changed {
+            map.set_tile(x, y, Tile::Floor);
+        }
+    }
+
+    false
+}
+
+// Deep lakes ringed by shallows
+pub fn add_lakes(map: &mut GameMap, start: &EntityPosition, count: usize, rng: &mut GameRng) {
+    for _ in 0..count {
+        add_pool(map, start, Tile::Water, Some(Tile::ShallowWater), rng);
+    }
+}
+
+// A band of shallow water meandering across the map from west to east. It
+// only floods floor, so it never blocks anything but slows everyone down.
+pub fn add_river(map: &mut GameMap, rng: &mut GameRng) {
+    if map.height < RIVER_WIDTH + 2 {
+        return;
+    }
+
+    let max_y = map.height - RIVER_WIDTH - 1;
+    let mut y = rng.random_range(map.height / 4..=max_y.max(map.height / 4));
+    for x in 1..map.width.saturating_sub(1) {
+        // Drift up, down or straight on
+        y = (y + rng.random_range(0..=2))
+            .saturating_sub(1)
+            .clamp(1, max_y);
+
+        for dy in 0..RIVER_WIDTH {
+            if map.tile(x, y + dy) == Tile::Floor {
+                map.set_tile(x, y + dy, Tile::ShallowWater);
+            }
+        }
+    }
+}
+
+fn reachable_mask(map: &GameMap, start: &EntityPosition) -> Vec<bool> {
+    let mut mask = vec![false; map.tiles.len()];
+    for position in reachable_tiles(map, start) {
+        mask[map.index(position.x, position.y)] = true;
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn pools_never_cut_off_the_start() {
+        let corridor = GameMap::from_rows(&[
+            "##########", //
+            "..........", //
+            "######.###", //
+            "######.###", //
+        ]);
+        let start = EntityPosition::new(0, 1);
+
+        for seed in 0..20 {
+            let mut map = corridor.clone();
+            add_pool(
+                &mut map,
+                &start,
+                Tile::Lava,
+                None,
+                &mut GameRng::seed_from_u64(seed),
+            );
+
+            // Only the lava itself is lost, never anything behind it
+            let reachable = reachable_tiles(&map, &start).len();
+            assert_eq!(reachable, 12 - count(&map, Tile::Lava), "seed {seed}");
+        }
+
+        let mut open = GameMap::from_tiles(vec![vec![Tile::Floor; 30]; 30]);
+        let mut rng = GameRng::seed_from_u64(3);
+        assert!(add_pool(
+            &mut open,
+            &start,
+            Tile::Water,
+            Some(Tile::ShallowWater),
+            &mut rng
+        ));
+        assert!(count(&open, Tile::Water) > 0);
+    }
+
+    fn count(map: &GameMap, tile: Tile) -> usize {
+        map.tiles.iter().filter(|t| **t == tile).count()
+    }
+}
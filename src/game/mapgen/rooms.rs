@@ -0,0 +1,137 @@
+use super::{GeneratedMap, Room, features};
+use crate::game::entities::EntityPosition;
+use crate::game::map::{GameMap, Tile};
+use crate::game::state::GameRng;
+use rand::Rng;
+
+const MAX_ROOMS: usize = 30;
+const ROOM_MIN_SIZE: usize = 6; // Including walls
+const ROOM_MAX_SIZE: usize = 14;
+
+// Random non-overlapping rooms, each joined to the previous one by an
+// L-shaped corridor. Doors are placed where a corridor enters a room and a
+// shallow river winds through the whole dungeon.
+pub fn generate(width: usize, height: usize, rng: &mut GameRng) -> GeneratedMap {
+    let mut tiles = vec![vec![Tile::Wall; width]; height];
+    let mut rooms: Vec<Room> = Vec::new();
+
+    for _ in 0..MAX_ROOMS {
+        let room_width = rng.random_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
+        let room_height = rng.random_range(ROOM_MIN_SIZE..=ROOM_MAX_SIZE);
+        if room_width >= width || room_height >= height {
+            continue;
+        }
+
+        let room = Room::new(
+            rng.random_range(0..width - room_width),
+            rng.random_range(0..height - room_height),
+            room_width,
+            room_height,
+        );
+
+        if rooms.iter().any(|other| room.intersects(other)) {
+            continue;
+        }
+
+        carve_room(&mut tiles, &room);
+
+        if let Some(previous) = rooms.last() {
+            let (from, to) = (previous.center(), room.center());
+            if rng.random_bool(0.5) {
+                carve_horizontal(&mut tiles, from.x, to.x, from.y);
+                carve_vertical(&mut tiles, from.y, to.y, to.x);
+            } else {
+                carve_vertical(&mut tiles, from.y, to.y, from.x);
+                carve_horizontal(&mut tiles, from.x, to.x, to.y);
+            }
+        }
+
+        rooms.push(room);
+    }
+
+    place_doors(&mut tiles, &rooms);
+
+    let player_start = rooms
+        .first()
+        .map(Room::center)
+        .unwrap_or_else(|| EntityPosition::new(width / 2, height / 2));
+
+    let mut map = GameMap::from_tiles(tiles);
+    features::add_river(&mut map, rng);
+
+    GeneratedMap {
+        map,
+        rooms,
+        player_start,
+    }
+}
+
+fn carve_room(tiles: &mut [Vec<Tile>], room: &Room) {
+    for row in tiles
+        .iter_mut()
+        .take(room.y + room.height - 1)
+        .skip(room.y + 1)
+    {
+        for tile in row
+            .iter_mut()
+            .take(room.x + room.width - 1)
+            .skip(room.x + 1)
+        {
+            *tile = Tile::Floor;
+        }
+    }
+}
+
+fn carve_horizontal(tiles: &mut [Vec<Tile>], x1: usize, x2: usize, y: usize) {
+    tiles[y][x1.min(x2)..=x1.max(x2)].fill(Tile::Floor);
+}
+
+fn carve_vertical(tiles: &mut [Vec<Tile>], y1: usize, y2: usize, x: usize) {
+    for row in tiles.iter_mut().take(y1.max(y2) + 1).skip(y1.min(y2)) {
+        row[x] = Tile::Floor;
+    }
+}
+
+// A corridor opening in a room's wall becomes a door when it is framed by
+// walls on both sides, so corridors running along a wall don't turn into a
+// row of doors
+fn place_doors(tiles: &mut [Vec<Tile>], rooms: &[Room]) {
+    let is_wall = |tiles: &[Vec<Tile>], x: usize, y: usize| matches!(tiles[y][x], Tile::Wall);
+
+    for room in rooms {
+        for y in room.y..room.y + room.height {
+            for x in room.x..room.x + room.width {
+                if !room.is_on_edge(x, y) || !matches!(tiles[y][x], Tile::Floor) {
+                    continue;
+                }
+                if x == 0 || y == 0 || y + 1 >= tiles.len() || x + 1 >= tiles[y].len() {
+                    continue;
+                }
+
+                let framed_horizontally = is_wall(tiles, x - 1, y) && is_wall(tiles, x + 1, y);
+                let framed_vertically = is_wall(tiles, x, y - 1) && is_wall(tiles, x, y + 1);
+                if framed_horizontally || framed_vertically {
+                    tiles[y][x] = Tile::Door;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::mapgen::find_regions;
+    use rand::SeedableRng;
+
+    #[test]
+    fn corridors_connect_every_room() {
+        for seed in 0..10 {
+            let generated = generate(80, 50, &mut GameRng::seed_from_u64(seed));
+            let start = &generated.player_start;
+
+            assert!(generated.map.is_walkable(start.x, start.y));
+            assert_eq!(find_regions(&generated.map).len(), 1);
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use super::{GeneratedMap, find_regions, position_near_center};
+use crate::game::entities::EntityPosition;
+use crate::game::map::{GameMap, Tile};
+use crate::game::state::GameRng;
+use rand::Rng;
+
+// Open field with walls sprinkled at random, the original test map
+pub fn generate(width: usize, height: usize, rng: &mut GameRng) -> GeneratedMap {
+    let mut tiles = vec![vec![Tile::Floor; width]; height];
+
+    // Add some random walls
+    for tile_row in tiles.iter_mut() {
+        for tile in tile_row.iter_mut() {
+            if rng.random_ratio(1, 10) {
+                *tile = Tile::Wall;
+            }
+        }
+    }
+    // Add borders
+    tiles[0].fill(Tile::Wall);
+    tiles[height - 1].fill(Tile::Wall);
+
+    for item in tiles.iter_mut().take(height) {
+        item[0] = Tile::Wall;
+        item[width - 1] = Tile::Wall;
+    }
+
+    let map = GameMap::from_tiles(tiles);
+
+    // Start in the largest open area so the player isn't boxed in
+    let player_start = find_regions(&map)
+        .iter()
+        .max_by_key(|region| region.len())
+        .map(|region| position_near_center(region, width, height))
+        .unwrap_or_else(|| EntityPosition::new(width / 2, height / 2));
+
+    GeneratedMap {
+        map,
+        rooms: Vec::new(),
+        player_start,
+    }
+}
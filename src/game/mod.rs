@@ -1,8 +1,20 @@
+pub mod action;
 pub mod action_log;
 pub mod ai;
+pub mod combat;
+pub mod dijkstra;
+pub mod engine;
 pub mod entities;
+pub mod event;
+pub mod fov;
+pub mod level;
 pub mod map;
+pub mod mapgen;
+pub mod occupancy;
+pub mod pathfinding;
 pub mod player;
+pub mod progression;
 pub mod state;
+pub mod terrain;
 
 use action_log::*;
@@ -0,0 +1,47 @@
+use super::entities::{Enemy, EntityPosition};
+
+// Which enemy stands on each tile, as an index into `GameState::enemies`.
+// Lets rendering, collision and targeting look enemies up by position
+// instead of scanning the whole enemy list.
+#[derive(Debug, Clone, Default)]
+pub struct Occupancy {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<Option<usize>>,
+}
+
+impl Occupancy {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![None; width * height],
+        }
+    }
+
+    pub fn build(width: usize, height: usize, enemies: &[Enemy]) -> Self {
+        let mut occupancy = Self::new(width, height);
+        for (index, enemy) in enemies.iter().enumerate() {
+            occupancy.set(&enemy.position, Some(index));
+        }
+        occupancy
+    }
+
+    fn cell(&self, pos: &EntityPosition) -> Option<usize> {
+        (pos.x < self.width && pos.y < self.height).then(|| pos.y * self.width + pos.x)
+    }
+
+    pub fn enemy_at(&self, pos: &EntityPosition) -> Option<usize> {
+        self.cell(pos).and_then(|cell| self.cells[cell])
+    }
+
+    pub fn is_occupied(&self, pos: &EntityPosition) -> bool {
+        self.enemy_at(pos).is_some()
+    }
+
+    pub fn set(&mut self, pos: &EntityPosition, enemy: Option<usize>) {
+        if let Some(cell) = self.cell(pos) {
+            self.cells[cell] = enemy;
+        }
+    }
+}
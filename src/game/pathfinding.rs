@@ -0,0 +1,265 @@
+use super::entities::EntityPosition;
+use super::map::GameMap;
+use super::terrain::Mobility;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+// Upper bound on expanded nodes so a single unreachable target can't stall a turn
+pub const DEFAULT_SEARCH_BUDGET: usize = 2000;
+
+// A* over the map with 4-way movement, following the terrain rules and
+// movement costs of `mobility`. Impassable terrain is always avoided and
+// `is_blocked` marks extra obstacles such as other entities; the goal itself
+// is never treated as blocked so a path can end next to (or on) a target.
+// Returns the steps to take, excluding `start` and including `goal`.
+pub fn find_path<F>(
+    map: &GameMap,
+    start: &EntityPosition,
+    goal: &EntityPosition,
+    mobility: Mobility,
+    is_blocked: F,
+    max_nodes: usize,
+) -> Option<Vec<EntityPosition>>
+where
+    F: Fn(&EntityPosition) -> bool,
+{
+    if start == goal {
+        return Some(Vec::new());
+    }
+    if !map.is_passable(goal.x, goal.y, mobility) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<EntityPosition, EntityPosition> = HashMap::new();
+    let mut cost_so_far: HashMap<EntityPosition, usize> = HashMap::new();
+    let mut expanded = 0;
+
+    cost_so_far.insert(start.clone(), 0);
+    open.push(Reverse((
+        manhattan_distance(start, goal),
+        0,
+        start.y,
+        start.x,
+    )));
+
+    while let Some(Reverse((_, cost, y, x))) = open.pop() {
+        let current = EntityPosition::new(x, y);
+        if current == *goal {
+            return Some(reconstruct_path(&came_from, start, goal));
+        }
+
+        // Skip stale queue entries that were superseded by a cheaper route
+        if cost > cost_so_far[&current] {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded > max_nodes {
+            return None;
+        }
+
+        for next in neighbours(map, &current, mobility) {
+            if next != *goal && is_blocked(&next) {
+                continue;
+            }
+
+            let next_cost = cost + map.movement_cost(next.x, next.y) as usize;
+            if cost_so_far
+                .get(&next)
+                .is_none_or(|&known| next_cost < known)
+            {
+                let priority = next_cost + manhattan_distance(&next, goal);
+                open.push(Reverse((priority, next_cost, next.y, next.x)));
+                cost_so_far.insert(next.clone(), next_cost);
+                came_from.insert(next, current.clone());
+            }
+        }
+    }
+
+    None
+}
+
+// First step of the path towards `goal`, if one exists within the budget
+pub fn next_step<F>(
+    map: &GameMap,
+    start: &EntityPosition,
+    goal: &EntityPosition,
+    mobility: Mobility,
+    is_blocked: F,
+    max_nodes: usize,
+) -> Option<EntityPosition>
+where
+    F: Fn(&EntityPosition) -> bool,
+{
+    find_path(map, start, goal, mobility, is_blocked, max_nodes)?
+        .into_iter()
+        .next()
+}
+
+pub fn manhattan_distance(a: &EntityPosition, b: &EntityPosition) -> usize {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+// Passable orthogonal neighbours of `pos`
+fn neighbours(map: &GameMap, pos: &EntityPosition, mobility: Mobility) -> Vec<EntityPosition> {
+    let mut result = Vec::with_capacity(4);
+
+    if pos.y > 0 {
+        result.push(EntityPosition::new(pos.x, pos.y - 1));
+    }
+    if pos.x > 0 {
+        result.push(EntityPosition::new(pos.x - 1, pos.y));
+    }
+    result.push(EntityPosition::new(pos.x + 1, pos.y));
+    result.push(EntityPosition::new(pos.x, pos.y + 1));
+
+    result.retain(|p| map.is_passable(p.x, p.y, mobility));
+    result
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<EntityPosition, EntityPosition>,
+    start: &EntityPosition,
+    goal: &EntityPosition,
+) -> Vec<EntityPosition> {
+    let mut path = vec![goal.clone()];
+    let mut current = goal;
+
+    while let Some(previous) = came_from.get(current) {
+        if previous == start {
+            break;
+        }
+        path.push(previous.clone());
+        current = previous;
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_straight_path_in_open_room() {
+        let map = GameMap::from_rows(&[".....", ".....", "....."]);
+        let path = find_path(
+            &map,
+            &EntityPosition::new(0, 1),
+            &EntityPosition::new(4, 1),
+            Mobility::Walker,
+            |_| false,
+            DEFAULT_SEARCH_BUDGET,
+        )
+        .unwrap();
+
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.last(), Some(&EntityPosition::new(4, 1)));
+    }
+
+    #[test]
+    fn routes_around_walls() {
+        let map = GameMap::from_rows(&[
+            ".....", //
+            ".###.", //
+            "..#..", //
+        ]);
+        let path = find_path(
+            &map,
+            &EntityPosition::new(1, 2),
+            &EntityPosition::new(3, 2),
+            Mobility::Walker,
+            |_| false,
+            DEFAULT_SEARCH_BUDGET,
+        )
+        .unwrap();
+
+        // Up the left side, across the top and back down the right side
+        assert_eq!(path.len(), 10);
+        assert!(path.iter().all(|p| map.is_walkable(p.x, p.y)));
+    }
+
+    #[test]
+    fn treats_blocked_tiles_as_obstacles_except_goal() {
+        let map = GameMap::from_rows(&[
+            "...", //
+            "...", //
+        ]);
+        let blocker = EntityPosition::new(1, 0);
+        let goal = EntityPosition::new(2, 0);
+        let path = find_path(
+            &map,
+            &EntityPosition::new(0, 0),
+            &goal,
+            Mobility::Walker,
+            |p| *p == blocker || *p == goal,
+            DEFAULT_SEARCH_BUDGET,
+        )
+        .unwrap();
+
+        assert!(!path.contains(&blocker));
+        assert_eq!(path.last(), Some(&goal));
+    }
+
+    #[test]
+    fn returns_none_when_unreachable_or_over_budget() {
+        let map = GameMap::from_rows(&[
+            "..#..", //
+            "..#..", //
+        ]);
+        let start = EntityPosition::new(0, 0);
+
+        assert!(
+            find_path(
+                &map,
+                &start,
+                &EntityPosition::new(4, 0),
+                Mobility::Walker,
+                |_| false,
+                DEFAULT_SEARCH_BUDGET
+            )
+            .is_none()
+        );
+
+        let open = GameMap::from_rows(&["........"]);
+        assert!(
+            find_path(
+                &open,
+                &start,
+                &EntityPosition::new(7, 0),
+                Mobility::Walker,
+                |_| false,
+                3
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn wades_only_when_going_around_costs_more() {
+        let map = GameMap::from_rows(&[
+            "..~~~..", //
+            ".......", //
+        ]);
+        let start = EntityPosition::new(0, 0);
+        let goal = EntityPosition::new(6, 0);
+        let path = find_path(&map, &start, &goal, Mobility::Walker, |_| false, 100).unwrap();
+        assert!(path.iter().all(|p| p.y == 1 || p.x < 2 || p.x > 4));
+
+        let narrow = GameMap::from_rows(&["..~~~.."]);
+        let path = find_path(&narrow, &start, &goal, Mobility::Walker, |_| false, 100).unwrap();
+        assert_eq!(path.len(), 6);
+    }
+
+    #[test]
+    fn only_flyers_cross_hazards() {
+        let map = GameMap::from_rows(&["..:.."]);
+        let start = EntityPosition::new(0, 0);
+        let goal = EntityPosition::new(4, 0);
+
+        assert!(find_path(&map, &start, &goal, Mobility::Walker, |_| false, 100).is_none());
+        assert!(find_path(&map, &start, &goal, Mobility::Flyer, |_| false, 100).is_some());
+    }
+}
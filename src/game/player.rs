@@ -1,6 +1,9 @@
-use super::{entities::EntityPosition, map::GameMap};
+use super::{
+    entities::EntityPosition, map::GameMap, progression::ProgressionCurve, terrain::Mobility,
+};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub name: String,
     pub position: EntityPosition,
@@ -10,9 +13,15 @@ pub struct Player {
     pub defense: u32,
     pub level: u32,
     pub exp: u32,
+    pub fov_radius: i32,
 }
 
+const DEFAULT_FOV_RADIUS: i32 = 10;
+
 impl Player {
+    // The hero always goes on foot
+    pub const MOBILITY: Mobility = Mobility::Walker;
+
     pub fn new(name: &str, hp: u32, attack: u32, defense: u32) -> Self {
         Self {
             name: name.to_string(),
@@ -23,11 +32,33 @@ impl Player {
             defense,
             level: 1,
             exp: 0,
+            fov_radius: DEFAULT_FOV_RADIUS,
         }
     }
 
+    // Adds exp and applies every level up it triggers. Returns the levels reached.
+    pub fn gain_exp(&mut self, amount: u32, curve: &ProgressionCurve) -> Vec<u32> {
+        let mut levels_reached = Vec::new();
+        self.exp += amount;
+
+        while self.exp >= curve.exp_to_next_level(self.level) {
+            self.exp -= curve.exp_to_next_level(self.level);
+            self.level += 1;
+            self.max_hp += curve.hp_per_level;
+            self.attack += curve.attack_per_level;
+            self.defense += curve.defense_per_level;
+
+            let heal = (self.max_hp as f32 * curve.heal_fraction).round() as u32;
+            self.hp = (self.hp + heal).min(self.max_hp);
+
+            levels_reached.push(self.level);
+        }
+
+        levels_reached
+    }
+
     pub fn move_up(&mut self, map: &GameMap) -> bool {
-        if self.position.y > 0 && !map.is_wall(self.position.x, self.position.y - 1) {
+        if self.position.y > 0 && map.is_walkable(self.position.x, self.position.y - 1) {
             self.position.y -= 1;
             true
         } else {
@@ -36,7 +67,8 @@ impl Player {
     }
 
     pub fn move_down(&mut self, map: &GameMap) -> bool {
-        if self.position.y < map.height - 1 && !map.is_wall(self.position.x, self.position.y + 1) {
+        if self.position.y < map.height - 1 && map.is_walkable(self.position.x, self.position.y + 1)
+        {
             self.position.y += 1;
             true
         } else {
@@ -45,7 +77,7 @@ impl Player {
     }
 
     pub fn move_left(&mut self, map: &GameMap) -> bool {
-        if self.position.x > 0 && !map.is_wall(self.position.x - 1, self.position.y) {
+        if self.position.x > 0 && map.is_walkable(self.position.x - 1, self.position.y) {
             self.position.x -= 1;
             true
         } else {
@@ -54,7 +86,8 @@ impl Player {
     }
 
     pub fn move_right(&mut self, map: &GameMap) -> bool {
-        if self.position.x < map.width - 1 && !map.is_wall(self.position.x + 1, self.position.y) {
+        if self.position.x < map.width - 1 && map.is_walkable(self.position.x + 1, self.position.y)
+        {
             self.position.x += 1;
             true
         } else {
@@ -62,3 +95,47 @@ impl Player {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_award_can_grant_several_levels() {
+        let curve = ProgressionCurve::default();
+        let mut player = Player::new("Hero", 100, 10, 5);
+
+        // 50 exp for level 2 and 75 more for level 3, 5 left over
+        let levels = player.gain_exp(130, &curve);
+
+        assert_eq!(levels, vec![2, 3]);
+        assert_eq!(player.level, 3);
+        assert_eq!(player.exp, 5);
+        assert_eq!(player.max_hp, 120);
+        assert_eq!(player.attack, 14);
+        assert_eq!(player.defense, 7);
+    }
+
+    #[test]
+    fn exp_short_of_a_level_is_kept() {
+        let curve = ProgressionCurve::default();
+        let mut player = Player::new("Hero", 100, 10, 5);
+
+        assert!(player.gain_exp(49, &curve).is_empty());
+        assert_eq!(player.gain_exp(1, &curve), vec![2]);
+        assert_eq!(player.exp, 0);
+    }
+
+    #[test]
+    fn level_up_heals_up_to_max_hp() {
+        let curve = ProgressionCurve::default();
+        let mut hurt = Player::new("Hero", 100, 10, 5);
+        hurt.hp = 10;
+        hurt.gain_exp(50, &curve);
+        assert_eq!(hurt.hp, 10 + 55); // Half of the new 110 max HP
+
+        let mut healthy = Player::new("Hero", 100, 10, 5);
+        healthy.gain_exp(50, &curve);
+        assert_eq!(healthy.hp, healthy.max_hp);
+    }
+}
@@ -0,0 +1,63 @@
+use super::entities::Enemy;
+use serde::{Deserialize, Serialize};
+
+// Describes how fast the player levels up and what each level grants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressionCurve {
+    pub base_exp: u32,         // Exp needed to go from level 1 to 2
+    pub exp_growth: f32,       // Multiplier applied to the requirement for every level
+    pub hp_per_level: u32,     // Max HP gained per level
+    pub attack_per_level: u32, // Attack gained per level
+    pub defense_per_level: u32,
+    pub heal_fraction: f32, // Portion of max HP restored on level up
+}
+
+impl Default for ProgressionCurve {
+    fn default() -> Self {
+        Self {
+            base_exp: 50,
+            exp_growth: 1.5,
+            hp_per_level: 10,
+            attack_per_level: 2,
+            defense_per_level: 1,
+            heal_fraction: 0.5,
+        }
+    }
+}
+
+impl ProgressionCurve {
+    // Exp needed to advance from `level` to `level + 1`
+    pub fn exp_to_next_level(&self, level: u32) -> u32 {
+        let exponent = level.saturating_sub(1) as i32;
+        ((self.base_exp as f32 * self.exp_growth.powi(exponent)).round() as u32).max(1)
+    }
+
+    // Tougher enemies are worth more exp
+    pub fn exp_for_enemy(&self, enemy: &Enemy) -> u32 {
+        (enemy.max_hp / 2 + enemy.attack * 2 + enemy.defense * 2).max(1) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_level_needs_more_exp() {
+        let curve = ProgressionCurve::default();
+
+        assert_eq!(curve.exp_to_next_level(1), 50);
+        assert_eq!(curve.exp_to_next_level(2), 75);
+        assert_eq!(curve.exp_to_next_level(3), 113);
+    }
+
+    #[test]
+    fn at_least_one_exp_is_always_needed() {
+        let curve = ProgressionCurve {
+            base_exp: 0,
+            ..ProgressionCurve::default()
+        };
+
+        assert_eq!(curve.exp_to_next_level(1), 1);
+    }
+}
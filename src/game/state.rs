@@ -1,13 +1,105 @@
 use super::ActionLog;
-use super::entities::Enemy;
+use super::entities::{Enemy, EntityPosition};
+use super::event::GameEvent;
+use super::fov::FieldOfView;
+use super::level::Level;
 use super::map::GameMap;
+use super::occupancy::Occupancy;
 use super::player::Player;
+use super::progression::ProgressionCurve;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Clone)]
+// Same algorithm as rand's StdRng, but its state can be written to a save and
+// its output doesn't change between rand releases, so seeds stay reproducible
+pub type GameRng = ChaCha12Rng;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub player: Player,
     pub map: GameMap,
     pub enemies: Vec<Enemy>,
     pub journal: Vec<ActionLog>,
     pub turn: u32,
+    pub stats: RunStats,
+    pub cause_of_death: Option<String>,
+    pub progression: ProgressionCurve,
+    #[serde(skip)]
+    pub fov: FieldOfView, // Tiles currently visible to the player, recomputed on load
+    #[serde(skip)]
+    pub occupancy: Occupancy, // Enemy on each tile, rebuilt on load
+    pub seed: u64, // Seed the level was generated from
+    pub generator_name: String,
+    #[serde(default)]
+    pub run_id: u64, // Tells runs on the same seed apart, names the replay file
+    pub rng: GameRng, // Every random decision in the run draws from this
+    pub depth: u32,   // Floor the player is on, starting at 1
+    pub levels: BTreeMap<u32, Level>, // Previously visited floors by depth
+}
+
+// Running totals for the current run, shown on the game over screen
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    pub kills: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+}
+
+impl RunStats {
+    pub fn record(&mut self, event: &GameEvent) {
+        match event {
+            GameEvent::PlayerAttacked { damage, .. } => self.damage_dealt += *damage as u32,
+            GameEvent::EnemyAttacked { damage, .. } | GameEvent::TerrainHurt { damage, .. } => {
+                self.damage_taken += *damage as u32
+            }
+            GameEvent::EnemyKilled { .. } => self.kills += 1,
+            _ => {}
+        }
+    }
+}
+
+impl GameState {
+    pub fn is_player_dead(&self) -> bool {
+        self.player.hp == 0
+    }
+
+    // Rebuilds the occupancy grid from scratch, needed whenever the map or the
+    // whole enemy list is swapped out
+    pub fn rebuild_occupancy(&mut self) {
+        self.occupancy = Occupancy::build(self.map.width, self.map.height, &self.enemies);
+    }
+
+    pub fn enemy_at(&self, pos: &EntityPosition) -> Option<usize> {
+        self.occupancy.enemy_at(pos)
+    }
+
+    pub fn add_enemy(&mut self, enemy: Enemy) {
+        self.occupancy
+            .set(&enemy.position, Some(self.enemies.len()));
+        self.enemies.push(enemy);
+    }
+
+    pub fn move_enemy(&mut self, index: usize, to: EntityPosition) {
+        let enemy = &mut self.enemies[index];
+        self.occupancy.set(&enemy.position, None);
+        self.occupancy.set(&to, Some(index));
+        enemy.position = to;
+    }
+
+    pub fn remove_enemy(&mut self, index: usize) -> Enemy {
+        let enemy = self.enemies.remove(index);
+        self.occupancy.set(&enemy.position, None);
+        // Everyone after the removed enemy moved down one slot
+        for (index, other) in self.enemies.iter().enumerate().skip(index) {
+            self.occupancy.set(&other.position, Some(index));
+        }
+        enemy
+    }
+
+    pub fn update_fov(&mut self) {
+        self.fov
+            .recompute(&self.map, &self.player.position, self.player.fov_radius);
+        self.map.mark_explored(&self.fov);
+    }
 }
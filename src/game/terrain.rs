@@ -0,0 +1,68 @@
+use super::map::Tile;
+use serde::{Deserialize, Serialize};
+
+// What a tile does to anyone who ends their turn on it without flying over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hazard {
+    Burns(u32), // Damage taken every turn spent on the tile
+    Fall,       // Instant death
+}
+
+// How a creature gets around. Decides which tiles it can enter and which
+// ones its routes avoid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mobility {
+    #[default]
+    Walker,
+    Swimmer, // Walks, and also crosses deep water
+    Flyer,   // Crosses water, lava and chasms alike
+}
+
+impl Mobility {
+    pub fn can_enter(self, tile: Tile) -> bool {
+        let def = tile.def();
+        match self {
+            Mobility::Walker => def.walkable,
+            Mobility::Swimmer => def.walkable || def.swimmable,
+            Mobility::Flyer => def.walkable || def.flyable,
+        }
+    }
+
+    pub fn is_hurt_by(self, tile: Tile) -> bool {
+        self != Mobility::Flyer && tile.def().hazard.is_some()
+    }
+
+    pub fn can_safely_enter(self, tile: Tile) -> bool {
+        self.can_enter(tile) && !self.is_hurt_by(tile)
+    }
+
+    // Whether a route may lead through the tile: anywhere that's safe to
+    // enter, plus closed doors that get opened on the way
+    pub fn can_path_through(self, tile: Tile) -> bool {
+        self.can_safely_enter(tile) || tile.def().opens_into.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_mobility_has_its_own_terrain_rules() {
+        assert!(!Mobility::Walker.can_enter(Tile::Water));
+        assert!(Mobility::Swimmer.can_enter(Tile::Water));
+        assert!(Mobility::Flyer.can_enter(Tile::Water));
+
+        assert!(Mobility::Walker.can_enter(Tile::ShallowWater));
+        assert!(Mobility::Walker.can_enter(Tile::Lava));
+        assert!(!Mobility::Walker.can_path_through(Tile::Lava));
+        assert!(!Mobility::Swimmer.can_path_through(Tile::Chasm));
+        assert!(Mobility::Flyer.can_path_through(Tile::Chasm));
+
+        for mobility in [Mobility::Walker, Mobility::Swimmer, Mobility::Flyer] {
+            assert!(!mobility.can_enter(Tile::Wall));
+            assert!(mobility.can_path_through(Tile::Door));
+        }
+    }
+}
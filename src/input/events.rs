@@ -4,6 +4,11 @@ use std::{
     time::{Duration, Instant},
 };
 
+pub enum AppEvent {
+    Input, // A terminal event is ready to be read
+    Tick,  // `tick_rate` has passed since the last tick
+}
+
 pub struct EventHandler {
     pub tick_rate: Duration,
     pub last_tick: Instant,
@@ -17,15 +22,16 @@ impl EventHandler {
         }
     }
 
-    pub fn next(&mut self) -> io::Result<bool> {
+    pub fn next(&mut self) -> io::Result<Option<AppEvent>> {
         if event::poll(Duration::from_millis(50))? {
-            return Ok(true);
+            return Ok(Some(AppEvent::Input));
         }
 
         if self.last_tick.elapsed() >= self.tick_rate {
             self.last_tick = Instant::now();
+            return Ok(Some(AppEvent::Tick));
         }
 
-        Ok(false)
+        Ok(None)
     }
 }
@@ -1,4 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent};
+use terminal_horizon::game::action::{Direction, GameAction};
 
 // Represents actions possible from the menu
 pub enum MenuAction {
@@ -8,18 +9,29 @@ pub enum MenuAction {
     Quit,
 }
 
-// Represents actions possible in the game
-pub enum GameAction {
-    MovePlayer(Direction), // Define Direction enum (Up, Down, Left, Right)
+// Represents inputs possible in the game: a move for the engine, or leaving
+pub enum GameInput {
+    Play(GameAction),
     OpenMenu,
     Quit,
 }
 
-pub enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+// Represents actions possible on the options screen
+pub enum OptionsAction {
+    NavigateUp,
+    NavigateDown,
+    PreviousValue,
+    NextValue,
+    TypeDigit(char),
+    DeleteDigit,
+    Back,
+}
+
+// Represents actions possible on the game over screen
+pub enum GameOverAction {
+    NewGame,
+    MainMenu,
+    Quit,
 }
 
 pub fn handle_menu_input(key: KeyEvent) -> Option<MenuAction> {
@@ -32,14 +44,44 @@ pub fn handle_menu_input(key: KeyEvent) -> Option<MenuAction> {
     }
 }
 
-pub fn handle_game_input(key: KeyEvent) -> Option<GameAction> {
+pub fn handle_game_input(key: KeyEvent) -> Option<GameInput> {
+    let action = match key.code {
+        KeyCode::Esc => return Some(GameInput::OpenMenu),
+        KeyCode::Char('q') => return Some(GameInput::Quit),
+        KeyCode::Up | KeyCode::Char('w') => GameAction::MovePlayer(Direction::Up),
+        KeyCode::Down | KeyCode::Char('s') => GameAction::MovePlayer(Direction::Down),
+        KeyCode::Left | KeyCode::Char('a') => GameAction::MovePlayer(Direction::Left),
+        KeyCode::Right | KeyCode::Char('d') => GameAction::MovePlayer(Direction::Right),
+        KeyCode::Char('W') => GameAction::CloseDoor(Direction::Up),
+        KeyCode::Char('S') => GameAction::CloseDoor(Direction::Down),
+        KeyCode::Char('A') => GameAction::CloseDoor(Direction::Left),
+        KeyCode::Char('D') => GameAction::CloseDoor(Direction::Right),
+        KeyCode::Char('x') => GameAction::AutoExplore,
+        KeyCode::Char('>') => GameAction::Descend,
+        KeyCode::Char('<') => GameAction::Ascend,
+        _ => return None,
+    };
+    Some(GameInput::Play(action))
+}
+
+pub fn handle_game_over_input(key: KeyEvent) -> Option<GameOverAction> {
+    match key.code {
+        KeyCode::Enter | KeyCode::Char('n') => Some(GameOverAction::NewGame),
+        KeyCode::Esc | KeyCode::Char('m') => Some(GameOverAction::MainMenu),
+        KeyCode::Char('q') => Some(GameOverAction::Quit),
+        _ => None,
+    }
+}
+
+pub fn handle_options_input(key: KeyEvent) -> Option<OptionsAction> {
     match key.code {
-        KeyCode::Esc => Some(GameAction::OpenMenu),
-        KeyCode::Char('q') => Some(GameAction::Quit),
-        KeyCode::Up | KeyCode::Char('w') => Some(GameAction::MovePlayer(Direction::Up)),
-        KeyCode::Down | KeyCode::Char('s') => Some(GameAction::MovePlayer(Direction::Down)),
-        KeyCode::Left | KeyCode::Char('a') => Some(GameAction::MovePlayer(Direction::Left)),
-        KeyCode::Right | KeyCode::Char('d') => Some(GameAction::MovePlayer(Direction::Right)),
+        KeyCode::Up => Some(OptionsAction::NavigateUp),
+        KeyCode::Down => Some(OptionsAction::NavigateDown),
+        KeyCode::Left => Some(OptionsAction::PreviousValue),
+        KeyCode::Right | KeyCode::Enter => Some(OptionsAction::NextValue),
+        KeyCode::Char(c) if c.is_ascii_digit() => Some(OptionsAction::TypeDigit(c)),
+        KeyCode::Backspace => Some(OptionsAction::DeleteDigit),
+        KeyCode::Esc | KeyCode::Char('q') => Some(OptionsAction::Back),
         _ => None,
     }
 }
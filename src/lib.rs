@@ -0,0 +1,8 @@
+// The game engine behind Terminal Horizon. The terminal frontend in main.rs
+// is built on top of it, and so can bots, tests and other frontends: create a
+// `game::engine::Game` and drive it with `Game::step`.
+pub mod errors;
+pub mod game;
+pub mod replay;
+pub mod save;
+pub mod sim;
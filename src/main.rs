@@ -1,8 +1,7 @@
 #![allow(dead_code)]
 
 mod app;
-mod errors;
-mod game;
+mod cli;
 mod input;
 mod ui;
 
@@ -12,13 +11,36 @@ use crossterm::{
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use errors::AppError;
-use input::events::EventHandler;
+use input::events::{AppEvent, EventHandler};
 use ratatui::{Terminal, prelude::*};
-use std::{error::Error, io};
+use std::{error::Error, io, process};
+use terminal_horizon::errors::AppError;
+use terminal_horizon::{game, replay, sim};
 use ui::ui;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) if options.show_help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Some(games) = options.simulate {
+        return run_simulation(&options, games);
+    }
+
+    // Load the replay before touching the terminal so errors stay readable
+    let mut app = match &options.replay {
+        Some(path) => App::from_replay(replay::load_replay(path)?)?,
+        None => App::new(),
+    };
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -26,9 +48,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app state
-    let mut app = App::new();
-    let event_handler = EventHandler::new(250);
+    let event_handler = EventHandler::new(options.tick_rate_ms);
 
     // Main loop
     let res = run_app(&mut terminal, &mut app, event_handler);
@@ -44,12 +64,31 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     // Handle potential errors
     if let Err(err) = res {
-        eprintln!("{err:?}");
+        eprintln!("Error: {err}");
     }
 
     Ok(())
 }
 
+// Headless batch of bot games, reports go to stdout and a summary to stderr
+fn run_simulation(options: &cli::CliOptions, games: u64) -> Result<(), Box<dyn Error>> {
+    let generator_name = options.generator.as_deref().unwrap_or("Rooms");
+    let generator = game::mapgen::generator_by_name(generator_name)
+        .ok_or_else(|| format!("unknown map generator '{}'", generator_name))?;
+
+    let config = sim::SimulationConfig {
+        games,
+        first_seed: options.seed,
+        generator,
+        max_turns: options.max_turns,
+    };
+    let reports = sim::run_batch(&config);
+
+    sim::write_reports(&mut io::stdout().lock(), &reports, options.format)?;
+    eprintln!("{}", sim::summarize(&reports));
+    Ok(())
+}
+
 fn run_app<B: Backend>(
     terminal: &mut Terminal<B>,
     app: &mut App,
@@ -59,10 +98,15 @@ fn run_app<B: Backend>(
         // Draw UI
         terminal.draw(|f| ui(f, app))?;
 
-        if event_handler
+        let event = event_handler
             .next()
-            .map_err(|e| AppError::EventError(e.to_string()))?
-        {
+            .map_err(|e| AppError::EventError(e.to_string()))?;
+
+        if let Some(AppEvent::Tick) = event {
+            app.on_tick()?;
+        }
+
+        if let Some(AppEvent::Input) = event {
             match app.handle_events() {
                 // handle_events now returns Result<(), AppError>
                 Ok(should_quit) => {
@@ -70,11 +114,8 @@ fn run_app<B: Backend>(
                         return Ok(());
                     }
                 }
-                Err(err) => {
-                    // Log error maybe, then return it
-                    eprintln!("Error during event handling: {}", err);
-                    return Err(err); // Propagate the specific error
-                }
+                // Reported by main once the terminal is restored
+                Err(err) => return Err(err),
             }
         }
     }
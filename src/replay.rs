@@ -0,0 +1,157 @@
+use crate::errors::AppError;
+use crate::game::action::GameAction;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Bump whenever the file layout, the recorded actions or the game rules change
+// in a way that makes older replays load or play out differently
+pub const REPLAY_VERSION: u32 = 5;
+
+const DATA_DIR_NAME: &str = "terminal_horizon";
+const REPLAY_DIR_NAME: &str = "replays";
+
+// Everything needed to play a run again: the seed and generator recreate the
+// starting state, the actions drive it exactly as the player did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub generator_name: String,
+    pub actions: Vec<RecordedAction>,
+}
+
+// First line of a replay file, each following line is one `RecordedAction`
+#[derive(Serialize, Deserialize)]
+struct ReplayHeader {
+    version: u32,
+    seed: u64,
+    generator_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedAction {
+    pub turn: u32, // Turn the action was taken on, used to detect desyncs
+    pub action: GameAction,
+}
+
+impl Replay {
+    pub fn new(seed: u64, generator_name: &str) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            generator_name: generator_name.to_string(),
+            actions: Vec::new(),
+        }
+    }
+}
+
+// Progress through a replay being played back
+pub struct Playback {
+    pub replay: Replay,
+    pub next: usize,                   // Index of the next action to apply
+    pub desynced_at_turn: Option<u32>, // Set when the run stopped matching the recording
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            desynced_at_turn: None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.actions.len() || self.desynced_at_turn.is_some()
+    }
+}
+
+// Replays are kept next to the save so QA can grab them
+pub fn default_replay_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(DATA_DIR_NAME).join(REPLAY_DIR_NAME))
+}
+
+// Every run gets its own file, so continuing a save appends to its own replay
+// even after newer runs were recorded
+pub fn replay_path(dir: &Path, seed: u64, run_id: u64) -> PathBuf {
+    dir.join(format!("{seed}-{run_id:016x}.jsonl"))
+}
+
+// Appends a run's actions to its replay file as they are taken. Each line is
+// flushed right away, so a run that panics or gets killed still leaves a
+// replay ending with the action that broke it.
+pub struct ReplayRecorder {
+    writer: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    // Writes `replay` to a fresh file and keeps it open for the actions to come
+    pub fn create(path: &Path, replay: &Replay) -> Result<Self, AppError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut recorder = Self {
+            writer: BufWriter::new(File::create(path)?),
+        };
+        recorder.write_line(&ReplayHeader {
+            version: replay.version,
+            seed: replay.seed,
+            generator_name: replay.generator_name.clone(),
+        })?;
+        for action in &replay.actions {
+            recorder.write_line(action)?;
+        }
+        recorder.writer.flush()?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, turn: u32, action: &GameAction) -> Result<(), AppError> {
+        self.write_line(&RecordedAction {
+            turn,
+            action: action.clone(),
+        })?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_line(&mut self, value: &impl Serialize) -> Result<(), AppError> {
+        serde_json::to_writer(&mut self.writer, value)
+            .map_err(|e| AppError::ReplayError(e.to_string()))?;
+        self.writer.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+pub fn load_replay(path: &Path) -> Result<Replay, AppError> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = contents.lines().peekable();
+
+    let header: ReplayHeader = serde_json::from_str(lines.next().unwrap_or_default())
+        .map_err(|e| AppError::ReplayError(e.to_string()))?;
+    if header.version != REPLAY_VERSION {
+        return Err(AppError::ReplayError(format!(
+            "replay version {} cannot be played by this game (expected {})",
+            header.version, REPLAY_VERSION
+        )));
+    }
+
+    let mut actions = Vec::new();
+    while let Some(line) = lines.next() {
+        match serde_json::from_str(line) {
+            Ok(action) => actions.push(action),
+            // A run killed mid-write can leave half a line at the end
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(AppError::ReplayError(e.to_string())),
+        }
+    }
+
+    Ok(Replay {
+        version: header.version,
+        seed: header.seed,
+        generator_name: header.generator_name,
+        actions,
+    })
+}
@@ -0,0 +1,92 @@
+use serde_json::{Value, json};
+
+// Upgrades the game state of an older save by one version
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+// MIGRATIONS[i] turns a version i + 1 save into version i + 2. Migrations
+// work on raw JSON and must never change once released, so they spell out
+// the old and new formats instead of using the current game types.
+pub const MIGRATIONS: &[Migration] = &[v1_to_v2, v2_to_v3];
+
+// Runs every migration between `version` and the current save version
+pub fn upgrade(game_state: &mut Value, version: u32) -> Result<(), String> {
+    let first = version
+        .checked_sub(1)
+        .ok_or("unknown save file version 0")? as usize;
+    for (offset, migration) in MIGRATIONS.iter().enumerate().skip(first) {
+        migration(game_state)
+            .map_err(|e| format!("upgrading from version {}: {}", offset + 1, e))?;
+    }
+    Ok(())
+}
+
+// v2 stores AI behaviours as a registry kind plus state instead of an enum
+// with one variant per behaviour
+fn v1_to_v2(game_state: &mut Value) -> Result<(), String> {
+    v1_to_v2_enemies(game_state.get_mut("enemies"))?;
+
+    if let Some(levels) = game_state.get_mut("levels").and_then(Value::as_object_mut) {
+        for level in levels.values_mut() {
+            v1_to_v2_enemies(level.get_mut("enemies"))?;
+        }
+    }
+    Ok(())
+}
+
+fn v1_to_v2_enemies(enemies: Option<&mut Value>) -> Result<(), String> {
+    let enemies = enemies
+        .and_then(Value::as_array_mut)
+        .ok_or("missing enemy list")?;
+
+    for enemy in enemies {
+        let behavior = enemy
+            .get_mut("ai_behavior")
+            .ok_or("enemy without an AI behaviour")?;
+        let (variant, state) = behavior
+            .as_object()
+            .and_then(|variants| variants.iter().next())
+            .ok_or("malformed AI behaviour")?;
+
+        let kind = match variant.as_str() {
+            "Basic" => "basic",
+            "Cowardly" => "cowardly",
+            other => return Err(format!("unknown AI behaviour '{}'", other)),
+        };
+        *behavior = json!({ "kind": kind, "state": state.clone() });
+    }
+    Ok(())
+}
+
+// v3 stores map tiles and the explored flags as flat row-major lists instead
+// of one list per row
+fn v2_to_v3(game_state: &mut Value) -> Result<(), String> {
+    v2_to_v3_map(game_state.get_mut("map"))?;
+
+    if let Some(levels) = game_state.get_mut("levels").and_then(Value::as_object_mut) {
+        for level in levels.values_mut() {
+            v2_to_v3_map(level.get_mut("map"))?;
+        }
+    }
+    Ok(())
+}
+
+fn v2_to_v3_map(map: Option<&mut Value>) -> Result<(), String> {
+    let map = map.ok_or("missing map")?;
+
+    for field in ["tiles", "explored"] {
+        let rows = map
+            .get_mut(field)
+            .and_then(Value::as_array_mut)
+            .ok_or_else(|| format!("missing map {}", field))?;
+
+        let mut flat = Vec::new();
+        for row in rows.drain(..) {
+            match row {
+                Value::Array(cells) => flat.extend(cells),
+                _ => return Err(format!("malformed map {}", field)),
+            }
+        }
+        *rows = flat;
+    }
+    Ok(())
+}
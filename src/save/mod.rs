@@ -0,0 +1,183 @@
+mod migrations;
+
+use crate::errors::AppError;
+use crate::game::state::GameState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bump whenever a change to the saved types breaks older save files, adding a
+// migration from the previous version and a fixture of the new one
+pub const SAVE_VERSION: u32 = 3;
+
+const SAVE_DIR_NAME: &str = "terminal_horizon";
+const SAVE_FILE_NAME: &str = "save.json";
+
+// The version header is read first, the game state is only deserialized once
+// it has been migrated to the current format
+#[derive(Serialize, Deserialize)]
+struct SaveFile<T> {
+    version: u32,
+    game_state: T,
+}
+
+// Location of the save in the user's data directory, if the platform has one
+pub fn default_save_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(SAVE_DIR_NAME).join(SAVE_FILE_NAME))
+}
+
+pub fn write_save(path: &Path, game_state: &GameState) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        game_state,
+    };
+    let contents = serde_json::to_string(&save).map_err(|e| AppError::SaveError(e.to_string()))?;
+
+    // Write next to the old save first so a crash mid-write can't corrupt it
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+pub fn load_save(path: &Path) -> Result<GameState, AppError> {
+    let contents = fs::read_to_string(path)?;
+    let save: SaveFile<Value> =
+        serde_json::from_str(&contents).map_err(|e| AppError::SaveError(e.to_string()))?;
+
+    if save.version > SAVE_VERSION {
+        return Err(AppError::SaveTooNew {
+            found: save.version,
+            supported: SAVE_VERSION,
+        });
+    }
+
+    let mut raw_state = save.game_state;
+    migrations::upgrade(&mut raw_state, save.version).map_err(AppError::SaveError)?;
+
+    let mut game_state: GameState =
+        serde_json::from_value(raw_state).map_err(|e| AppError::SaveError(e.to_string()))?;
+    game_state.rebuild_occupancy();
+    game_state.update_fov();
+    Ok(game_state)
+}
+
+pub fn delete_save(path: &Path) -> Result<(), AppError> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::engine::Game;
+    use crate::game::map::Tile;
+    use crate::game::mapgen::GENERATORS;
+    use rand::Rng;
+
+    fn temp_save_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("terminal_horizon_test_{}", std::process::id()))
+            .join(name)
+    }
+
+    #[test]
+    fn saved_game_loads_back_unchanged() {
+        let mut game_state = Game::new(GENERATORS[0], 7).state;
+        let path = temp_save_path("roundtrip.json");
+
+        write_save(&path, &game_state).unwrap();
+        let mut loaded = load_save(&path).unwrap();
+        delete_save(&path).unwrap();
+
+        assert_eq!(loaded.player.position, game_state.player.position);
+        assert_eq!(loaded.map.tiles, game_state.map.tiles);
+        assert_eq!(loaded.enemies.len(), game_state.enemies.len());
+        for (loaded, original) in loaded.enemies.iter().zip(&game_state.enemies) {
+            assert_eq!(
+                format!("{:?}", loaded.ai_behavior),
+                format!("{:?}", original.ai_behavior)
+            );
+        }
+        // The run continues with the same random numbers
+        assert_eq!(loaded.rng.random::<u64>(), game_state.rng.random::<u64>());
+    }
+
+    fn load_fixture(version: u32) -> Result<GameState, AppError> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/saves")
+            .join(format!("v{}.json", version));
+        load_save(&path)
+    }
+
+    #[test]
+    fn every_version_has_a_migration() {
+        assert_eq!(migrations::MIGRATIONS.len() as u32, SAVE_VERSION - 1);
+    }
+
+    // Every fixture holds the same run, saved by each released version
+    #[test]
+    fn saves_from_every_version_load() {
+        for version in 1..=SAVE_VERSION {
+            let game_state = load_fixture(version)
+                .unwrap_or_else(|e| panic!("version {} fixture failed to load: {}", version, e));
+
+            assert_eq!(game_state.player.hp, 87, "version {}", version);
+            assert_eq!(game_state.depth, 2, "version {}", version);
+            assert_eq!(game_state.map.tile(2, 2), Tile::Door, "version {}", version);
+            assert!(game_state.map.is_explored(1, 1), "version {}", version);
+            assert_eq!(
+                game_state.levels[&1].enemies.len(),
+                1,
+                "version {}",
+                version
+            );
+
+            let kinds: Vec<&str> = game_state
+                .enemies
+                .iter()
+                .map(|e| e.ai_behavior.kind())
+                .collect();
+            assert_eq!(kinds, ["basic", "cowardly"], "version {}", version);
+
+            let goblin = game_state.enemies[0].ai_behavior.to_saved().unwrap();
+            assert_eq!(goblin.state["state"], "Chasing", "version {}", version);
+            assert_eq!(
+                goblin.state["last_known_player_pos"]["x"], 1,
+                "version {}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn newer_save_version_is_rejected() {
+        let path = temp_save_path("future.json");
+        let game_state = Game::new(GENERATORS[0], 7).state;
+        write_save(&path, &game_state).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap().replacen(
+            &format!("\"version\":{}", SAVE_VERSION),
+            "\"version\":999",
+            1,
+        );
+        fs::write(&path, contents).unwrap();
+
+        let result = load_save(&path);
+        delete_save(&path).unwrap();
+        assert!(matches!(
+            result,
+            Err(AppError::SaveTooNew {
+                found: 999,
+                supported: SAVE_VERSION
+            })
+        ));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::errors::AppError;
+use crate::game::action::{Direction, GameAction};
+use crate::game::dijkstra::DijkstraMap;
+use crate::game::engine::Game;
+use crate::game::entities::EntityPosition;
+use crate::game::map::Tile;
+use crate::game::mapgen::MapGenerator;
+use crate::game::pathfinding;
+use crate::game::player::Player;
+use crate::game::state::GameState;
+use serde::Serialize;
+use std::io::Write;
+use std::thread;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+}
+
+pub struct SimulationConfig {
+    pub games: u64,
+    pub first_seed: u64, // Games use consecutive seeds starting here
+    pub generator: &'static dyn MapGenerator,
+    pub max_turns: u32,
+}
+
+// How a simulated game ended
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Died,
+    TurnLimit,
+    Stuck, // The bot found nothing left to do
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Died => "died",
+            Outcome::TurnLimit => "turn_limit",
+            Outcome::Stuck => "stuck",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GameReport {
+    pub seed: u64,
+    pub generator: String,
+    pub outcome: Outcome,
+    pub turns: u32,
+    pub depth: u32,
+    pub level: u32,
+    pub kills: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+}
+
+// Plays every game of the batch, spread over the available cores. Reports
+// come back in seed order so the output doesn't depend on scheduling.
+pub fn run_batch(config: &SimulationConfig) -> Vec<GameReport> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get() as u64);
+    let seeds: Vec<u64> = (0..config.games)
+        .map(|game| config.first_seed.wrapping_add(game))
+        .collect();
+    let chunk_size = config.games.div_ceil(threads).max(1) as usize;
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = seeds
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|seed| simulate_game(config.generator, *seed, config.max_turns))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("simulation thread panicked"))
+            .collect()
+    })
+}
+
+// Plays one game with the bot through the same engine as the terminal game,
+// until the hero dies, runs out of turns or gets stuck
+pub fn simulate_game(generator: &dyn MapGenerator, seed: u64, max_turns: u32) -> GameReport {
+    let mut game = Game::new(generator, seed);
+
+    let outcome = loop {
+        if game.is_over() {
+            break Outcome::Died;
+        }
+        if game.state.turn >= max_turns {
+            break Outcome::TurnLimit;
+        }
+
+        let Some(action) = choose_action(&game.state) else {
+            break Outcome::Stuck;
+        };
+        let turn = game.state.turn;
+        game.step(action);
+        if game.state.turn == turn {
+            break Outcome::Stuck;
+        }
+    };
+
+    let game_state = &game.state;
+    GameReport {
+        seed,
+        generator: generator.name().to_string(),
+        outcome,
+        turns: game_state.turn,
+        depth: game_state.depth,
+        level: game_state.player.level,
+        kills: game_state.stats.kills,
+        damage_dealt: game_state.stats.damage_dealt,
+        damage_taken: game_state.stats.damage_taken,
+    }
+}
+
+// A simple aggressive explorer: fight anything in sight, otherwise explore
+// until the stairs down turn up and take them
+fn choose_action(game_state: &GameState) -> Option<GameAction> {
+    let player = &game_state.player.position;
+    let map = &game_state.map;
+    let is_enemy = |pos: &EntityPosition| game_state.occupancy.is_occupied(pos);
+
+    let nearest_visible_enemy = game_state
+        .enemies
+        .iter()
+        .filter(|e| game_state.fov.is_visible(e.position.x, e.position.y))
+        .min_by_key(|e| pathfinding::manhattan_distance(&e.position, player));
+
+    // Walking into an enemy attacks it, so chasing and fighting are the same
+    if let Some(enemy) = nearest_visible_enemy {
+        let step = pathfinding::next_step(
+            map,
+            player,
+            &enemy.position,
+            Player::MOBILITY,
+            |pos| *pos != enemy.position && is_enemy(pos),
+            pathfinding::DEFAULT_SEARCH_BUDGET,
+        );
+        if let Some(step) = step {
+            return direction_towards(player, &step).map(GameAction::MovePlayer);
+        }
+    }
+
+    if map.tile(player.x, player.y) == Tile::StairsDown {
+        return Some(GameAction::Descend);
+    }
+
+    // Head down as soon as the stairs have been seen, explore until then
+    let goals = match map.find_tile(Tile::StairsDown) {
+        Some(stairs) if map.is_explored(stairs.x, stairs.y) => vec![stairs],
+        _ => map.unexplored_tiles(),
+    };
+    let step = DijkstraMap::new(map, &goals, Player::MOBILITY).best_step(player, is_enemy)?;
+    direction_towards(player, &step).map(GameAction::MovePlayer)
+}
+
+fn direction_towards(from: &EntityPosition, to: &EntityPosition) -> Option<Direction> {
+    match (to.x as i64 - from.x as i64, to.y as i64 - from.y as i64) {
+        (0, -1) => Some(Direction::Up),
+        (0, 1) => Some(Direction::Down),
+        (-1, 0) => Some(Direction::Left),
+        (1, 0) => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+pub fn write_reports(
+    out: &mut impl Write,
+    reports: &[GameReport],
+    format: OutputFormat,
+) -> Result<(), AppError> {
+    match format {
+        OutputFormat::Csv => {
+            writeln!(
+                out,
+                "seed,generator,outcome,turns,depth,level,kills,damage_dealt,damage_taken"
+            )?;
+            for report in reports {
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{}",
+                    report.seed,
+                    report.generator,
+                    report.outcome.as_str(),
+                    report.turns,
+                    report.depth,
+                    report.level,
+                    report.kills,
+                    report.damage_dealt,
+                    report.damage_taken
+                )?;
+            }
+        }
+        OutputFormat::Json => {
+            serde_json::to_writer_pretty(&mut *out, reports)
+                .map_err(|e| AppError::StateError(e.to_string()))?;
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+// One line overview of a batch, printed next to the full reports
+pub fn summarize(reports: &[GameReport]) -> String {
+    let games = reports.len().max(1) as f64;
+    let average = |value: fn(&GameReport) -> u32| {
+        reports.iter().map(|r| value(r) as f64).sum::<f64>() / games
+    };
+    let deaths = reports
+        .iter()
+        .filter(|r| r.outcome == Outcome::Died)
+        .count();
+
+    format!(
+        "{} games: {:.1}% died, avg {:.1} turns, {:.2} depth, {:.1} kills, {:.1} damage taken",
+        reports.len(),
+        deaths as f64 * 100.0 / games,
+        average(|r| r.turns),
+        average(|r| r.depth),
+        average(|r| r.kills),
+        average(|r| r.damage_taken),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::mapgen::GENERATORS;
+
+    #[test]
+    fn simulated_games_are_reproducible() {
+        let first = simulate_game(GENERATORS[0], 11, 150);
+        let second = simulate_game(GENERATORS[0], 11, 150);
+
+        assert!(first.turns > 0 && first.turns <= 150);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap()
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use crate::app::App;
-use crate::game::action_log::ActionLog;
-use crate::game::entities::EntityPosition;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Rect};
 use ratatui::widgets::{Block, BorderType, Borders, List, ListItem};
+use terminal_horizon::game::action_log::ActionLog;
+use terminal_horizon::game::entities::EntityPosition;
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let journal_lines: Vec<String> = app
-        .game_state
+        .game
+        .state
         .journal
         .iter()
         .rev()
@@ -30,14 +31,14 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(journal_entries_widget, area);
 }
 
-fn create_log_entry(action_log: &ActionLog) -> String {
+pub fn create_log_entry(action_log: &ActionLog) -> String {
     let log_message = match &action_log.action_type {
-        crate::game::action_log::ActionType::Movement {
+        terminal_horizon::game::action_log::ActionType::Movement {
             position: EntityPosition { x, y },
         } => {
             format!("Player moved into: ({}, {})", x, y)
         }
-        crate::game::action_log::ActionType::MonsterAttack {
+        terminal_horizon::game::action_log::ActionType::MonsterAttack {
             attacker_name,
             target_name,
             damage,
@@ -45,6 +46,45 @@ fn create_log_entry(action_log: &ActionLog) -> String {
             "{} attacked {} for {} damage.",
             attacker_name, target_name, damage
         ),
+        terminal_horizon::game::action_log::ActionType::PlayerAttack {
+            target_name,
+            damage,
+        } => format!("You hit {} for {} damage.", target_name, damage),
+        terminal_horizon::game::action_log::ActionType::TerrainDamage { terrain, damage } => {
+            format!("The {} hurts you for {} damage.", terrain, damage)
+        }
+        terminal_horizon::game::action_log::ActionType::EnemyKilled { name } => {
+            format!("{} dies.", name)
+        }
+        terminal_horizon::game::action_log::ActionType::PlayerDied { killer_name } => {
+            format!("You were slain by {}.", killer_name)
+        }
+        terminal_horizon::game::action_log::ActionType::EnemySpotted { name } => {
+            format!("You spot a {}.", name)
+        }
+        terminal_horizon::game::action_log::ActionType::ExplorationComplete => {
+            "Nothing left to explore.".to_string()
+        }
+        terminal_horizon::game::action_log::ActionType::DoorOpened {
+            by,
+            position: EntityPosition { x, y },
+        } => format!("{} opened the door at ({}, {}).", by, x, y),
+        terminal_horizon::game::action_log::ActionType::DoorClosed {
+            by,
+            position: EntityPosition { x, y },
+        } => format!("{} closed the door at ({}, {}).", by, x, y),
+        terminal_horizon::game::action_log::ActionType::LevelChanged { depth } => {
+            format!("You take the stairs to depth {}.", depth)
+        }
+        terminal_horizon::game::action_log::ActionType::LevelUp {
+            level,
+            max_hp,
+            attack,
+            defense,
+        } => format!(
+            "Level up! You are now level {} (HP {}, ATK {}, DEF {}).",
+            level, max_hp, attack, defense
+        ),
     };
 
     format!("[Turn: {}]: {}", action_log.turn, log_message)
@@ -0,0 +1,112 @@
+use crate::app::App;
+use crate::ui::app_log::create_log_entry;
+use ratatui::{prelude::*, widgets::*};
+
+const LAST_ENTRIES_COUNT: usize = 8;
+
+pub fn render(f: &mut Frame, app: &App) {
+    let size = f.area();
+    let game_state = &app.game.state;
+
+    let block = Block::default()
+        .title("Game Over")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+
+    f.render_widget(block, size);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(2)
+        .constraints([
+            Constraint::Length(3),                             // Title
+            Constraint::Length(8),                             // Run summary
+            Constraint::Length(LAST_ENTRIES_COUNT as u16 + 2), // Last journal entries
+            Constraint::Min(0),
+            Constraint::Length(1), // Footer
+        ])
+        .split(size);
+
+    let title = Paragraph::new("You have died")
+        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+
+    f.render_widget(title, chunks[0]);
+
+    // Run summary
+    let cause = game_state
+        .cause_of_death
+        .clone()
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let summary = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Cause of death: ", Style::default().fg(Color::Gray)),
+            Span::styled(cause, Style::default().fg(Color::LightRed)),
+        ]),
+        Line::from(vec![
+            Span::styled("Turns survived: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                game_state.turn.to_string(),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Depth reached: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                game_state.depth.to_string(),
+                Style::default().fg(Color::LightCyan),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Level: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                game_state.player.level.to_string(),
+                Style::default().fg(Color::Green),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Seed: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{} ({})", game_state.seed, game_state.generator_name),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Kills: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                game_state.stats.kills.to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+        ]),
+    ])
+    .alignment(Alignment::Center);
+
+    f.render_widget(summary, chunks[1]);
+
+    // Last journal entries, oldest first
+    let mut last_entries: Vec<ListItem> = game_state
+        .journal
+        .iter()
+        .rev()
+        .take(LAST_ENTRIES_COUNT)
+        .map(|log| ListItem::from(create_log_entry(log)))
+        .collect();
+    last_entries.reverse();
+
+    let journal = List::new(last_entries).block(
+        Block::default()
+            .title("Last moments")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded),
+    );
+
+    f.render_widget(journal, chunks[2]);
+
+    let footer = Paragraph::new("Enter/N: new run   Esc/M: main menu   Q: quit")
+        .style(Style::default().fg(Color::DarkGray))
+        .alignment(Alignment::Center);
+
+    f.render_widget(footer, chunks[4]);
+}
@@ -29,12 +29,18 @@ pub fn render(f: &mut Frame, app: &App) {
         .iter()
         .enumerate()
         .map(|(i, item)| {
+            // Continue is greyed out until there is a save to load
+            let color = if i == 1 && !app.has_save {
+                Color::DarkGray
+            } else if i == app.menu_index {
+                Color::Yellow
+            } else {
+                Color::White
+            };
             let style = if i == app.menu_index {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
+                Style::default().fg(color).add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(color)
             };
 
             let text = Text::raw(item).style(style).alignment(Alignment::Center);
@@ -66,6 +72,24 @@ pub fn render(f: &mut Frame, app: &App) {
 
     f.render_widget(title, title_area);
 
+    if let Some(message) = &app.menu_message {
+        let message = Paragraph::new(message.as_str())
+            .style(Style::default().fg(Color::Red))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+
+        let message_area = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Percentage(75),
+                Constraint::Length(2),
+                Constraint::Min(0),
+            ])
+            .split(size)[1];
+
+        f.render_widget(message, message_area);
+    }
+
     let footer = Paragraph::new("Use ↑/↓ to navigate and Enter to select. Press 'q' to quit.")
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center);
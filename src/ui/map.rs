@@ -1,5 +1,7 @@
 use crate::app::App;
 use ratatui::{prelude::*, widgets::*};
+use terminal_horizon::game::entities::EntityPosition;
+use terminal_horizon::game::map::TileColor;
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     // Create a block for the map
@@ -15,9 +17,9 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let inner_area = map_block.inner(area);
 
     // Get the map and player
-    let map = &app.game_state.map;
-    let player = &app.game_state.player;
-    let enemies = &app.game_state.enemies;
+    let map = &app.game.state.map;
+    let player = &app.game.state.player;
+    let fov = &app.game.state.fov;
 
     // Calculate viewport - center on player
     let viewport_width = inner_area.width as usize;
@@ -43,13 +45,28 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 continue;
             }
 
+            let visible = fov.is_visible(x, y);
+
+            // Tiles never seen stay blank
+            if !visible && !map.is_explored(x, y) {
+                continue;
+            }
+
             // Default with map tiles
             let mut symbol = map.get_tile_symbol(x, y);
-            let mut style: Style = match map.tiles[y][x] {
-                crate::game::map::Tile::Floor => Style::default().fg(Color::DarkGray),
-                crate::game::map::Tile::Wall => Style::default().fg(Color::White),
-                crate::game::map::Tile::Door => Style::default().fg(Color::LightYellow),
-                crate::game::map::Tile::Water => Style::default().fg(Color::Blue),
+            let mut style: Style = if visible {
+                let def = map.tile(x, y).def();
+                let style = Style::default().fg(tile_color(def.color));
+                if def.bold {
+                    style.add_modifier(Modifier::BOLD)
+                } else {
+                    style
+                }
+            } else {
+                // Remembered but out of sight
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::DIM)
             };
 
             // Override if player
@@ -58,12 +75,9 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 style = Style::default().fg(Color::Yellow)
             }
 
-            // Override if enemy
-            if let Some(enemy) = enemies
-                .iter()
-                .find(|&e| e.position.x == x && e.position.y == y)
-            {
-                symbol = &enemy.symbol;
+            // Override if enemy, but only while the player can see it
+            if visible && let Some(index) = app.game.state.enemy_at(&EntityPosition::new(x, y)) {
+                symbol = &app.game.state.enemies[index].symbol;
                 style = Style::default().fg(Color::LightRed); // enemy color
             }
 
@@ -74,3 +88,15 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         }
     }
 }
+
+fn tile_color(color: TileColor) -> Color {
+    match color {
+        TileColor::DarkGray => Color::DarkGray,
+        TileColor::White => Color::White,
+        TileColor::LightYellow => Color::LightYellow,
+        TileColor::Blue => Color::Blue,
+        TileColor::LightCyan => Color::LightCyan,
+        TileColor::Cyan => Color::Cyan,
+        TileColor::Red => Color::Red,
+    }
+}
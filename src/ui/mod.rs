@@ -1,8 +1,10 @@
 pub mod app_log;
 pub mod game_area;
+pub mod game_over;
 pub mod game_ui;
 pub mod main_menu;
 pub mod map;
+pub mod options;
 pub mod sidebar;
 
 use crate::app::{App, AppScreen};
@@ -12,6 +14,7 @@ pub fn ui(f: &mut Frame, app: &App) {
     match app.screen {
         AppScreen::MainMenu => main_menu::render(f, app),
         AppScreen::Game => game_ui::render(f, app),
-        AppScreen::Options => todo!(),
+        AppScreen::GameOver => game_over::render(f, app),
+        AppScreen::Options => options::render(f, app),
     }
 }
@@ -0,0 +1,69 @@
+use crate::app::App;
+use ratatui::{prelude::*, widgets::*};
+use terminal_horizon::game::mapgen::GENERATORS;
+
+pub fn render(f: &mut Frame, app: &App) {
+    let size = f.area();
+
+    let block = Block::default()
+        .title("Options")
+        .title_alignment(Alignment::Center)
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+
+    f.render_widget(block, size);
+
+    let seed = if app.seed_input.is_empty() {
+        "random".to_string()
+    } else {
+        app.seed_input.clone()
+    };
+
+    let option_lines = [
+        format!(
+            "Map generator: < {} >",
+            GENERATORS[app.generator_index].name()
+        ),
+        format!("Seed: {}", seed),
+    ];
+
+    let options_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Length(option_lines.len() as u16 * 3),
+            Constraint::Percentage(30),
+        ])
+        .split(size)[1];
+
+    let options: Vec<ListItem> = option_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let style = if i == app.options_index {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+
+            ListItem::new(Text::raw(line).style(style).alignment(Alignment::Center))
+        })
+        .collect();
+
+    f.render_widget(List::new(options), options_area);
+
+    let footer = Paragraph::new(
+        "↑/↓: select   ←/→: change generator   0-9/Backspace: edit seed   Esc: back",
+    )
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center);
+
+    let footer_area = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(90), Constraint::Percentage(10)])
+        .split(size)[1];
+
+    f.render_widget(footer, footer_area);
+}
@@ -2,7 +2,7 @@ use crate::app::App;
 use ratatui::{prelude::*, widgets::*};
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    let player = &app.game_state.player;
+    let player = &app.game.state.player;
 
     // Create blocks for different sections
     let chunks = Layout::default()
@@ -11,7 +11,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             Constraint::Length(9), // Player info
             Constraint::Length(7), // Stats
             Constraint::Min(3),    // Controls
-            Constraint::Length(3), // Turn Number
+            Constraint::Length(5), // Turn number, depth and seed
         ])
         .split(area);
 
@@ -39,7 +39,14 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         ]),
         Line::from(vec![
             Span::styled("EXP: ", Style::default().fg(Color::Gray)),
-            Span::styled(player.exp.to_string(), Style::default().fg(Color::Blue)),
+            Span::styled(
+                format!(
+                    "{}/{}",
+                    player.exp,
+                    app.game.state.progression.exp_to_next_level(player.level)
+                ),
+                Style::default().fg(Color::Blue),
+            ),
         ]),
     ])
     .block(player_block);
@@ -79,18 +86,59 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(stats_info, chunks[1]);
 
-    // Controls block
+    // Controls block, replaced by the replay progress while watching one
     let controls_block = Block::default()
-        .title("Controls")
+        .title(if app.playback.is_some() {
+            "Replay"
+        } else {
+            "Controls"
+        })
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
-    let controls_info = Paragraph::new(vec![
-        Line::from("Movement: ↑/↓/←/→ or WASD"),
-        Line::from("ESC: Return to menu"),
-        Line::from("Q: Quit game"),
-    ])
-    .block(controls_block);
+    let controls_lines = match &app.playback {
+        Some(playback) => {
+            let status = match playback.desynced_at_turn {
+                Some(turn) => Span::styled(
+                    format!("Desynced at turn {}", turn),
+                    Style::default().fg(Color::Red),
+                ),
+                None if playback.is_finished() => {
+                    Span::styled("Finished", Style::default().fg(Color::Green))
+                }
+                None => Span::styled("Playing", Style::default().fg(Color::Yellow)),
+            };
+            vec![
+                Line::from(status),
+                Line::from(format!(
+                    "Action: {}/{}",
+                    playback.next,
+                    playback.replay.actions.len()
+                )),
+                Line::from("ESC: Return to menu"),
+                Line::from("Q: Quit game"),
+            ]
+        }
+        None => {
+            let mut lines = vec![
+                Line::from("Movement: ↑/↓/←/→ or WASD"),
+                Line::from("X: Auto-explore"),
+                Line::from("</>: Use stairs"),
+                Line::from("Shift+WASD: Close door"),
+                Line::from("ESC: Return to menu"),
+                Line::from("Q: Quit game"),
+            ];
+            // The menu says why once the player goes back to it
+            if app.replay_dir.is_some() && app.recorder.is_none() {
+                lines.push(Line::styled(
+                    "Not recording a replay",
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            lines
+        }
+    };
+    let controls_info = Paragraph::new(controls_lines).block(controls_block);
 
     f.render_widget(controls_info, chunks[2]);
 
@@ -100,13 +148,32 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded);
 
-    let turn_info = Paragraph::new(vec![Line::from(vec![
-        Span::styled("Turn: ", Style::default().fg(Color::Gray)),
-        Span::styled(
-            app.game_state.turn.to_string(),
-            Style::default().fg(Color::White),
-        ),
-    ])])
+    let turn_info = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Turn: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                app.game.state.turn.to_string(),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Depth: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                app.game.state.depth.to_string(),
+                Style::default().fg(Color::LightCyan),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("Seed: ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!(
+                    "{} ({})",
+                    app.game.state.seed, app.game.state.generator_name
+                ),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+    ])
     .block(turn_block);
 
     f.render_widget(turn_info, chunks[3]);
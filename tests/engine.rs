@@ -0,0 +1,249 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use terminal_horizon::game::action::{Direction, GameAction};
+use terminal_horizon::game::engine::Game;
+use terminal_horizon::game::entities::{Enemy, EntityPosition};
+use terminal_horizon::game::event::{EventSubscriber, GameEvent};
+use terminal_horizon::game::fov::FieldOfView;
+use terminal_horizon::game::map::{GameMap, Tile};
+use terminal_horizon::game::mapgen::GENERATORS;
+use terminal_horizon::game::terrain::Mobility;
+
+fn play(seed: u64) -> Game {
+    let mut game = Game::new(GENERATORS[0], seed);
+
+    for _ in 0..10 {
+        game.step(GameAction::AutoExplore);
+        for direction in [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ] {
+            game.step(GameAction::MovePlayer(direction));
+        }
+    }
+
+    game
+}
+
+#[test]
+fn same_seed_and_actions_give_same_run() {
+    let first = play(1234);
+    let second = play(1234);
+
+    let enemies = |game: &Game| -> Vec<(EntityPosition, i32)> {
+        game.state
+            .enemies
+            .iter()
+            .map(|e| (e.position.clone(), e.hp))
+            .collect()
+    };
+
+    assert_eq!(first.state.turn, second.state.turn);
+    assert_eq!(first.state.player.position, second.state.player.position);
+    assert_eq!(first.state.player.hp, second.state.player.hp);
+    assert_eq!(enemies(&first), enemies(&second));
+    assert_eq!(first.state.journal.len(), second.state.journal.len());
+}
+
+#[test]
+fn step_reports_what_happened() {
+    let mut game = Game::new(GENERATORS[0], 99);
+
+    let events = game.step(GameAction::AutoExplore);
+
+    assert!(!events.is_empty());
+    assert!(game.state.turn > 0);
+    // Every explored step either moves the hero, wading taking longer, or
+    // opens a door
+    let turns: u32 = events
+        .iter()
+        .map(|event| match event {
+            GameEvent::PlayerMoved { to, .. } => game.state.map.movement_cost(to.x, to.y),
+            GameEvent::DoorOpened { by, .. } if *by == game.state.player.name => 1,
+            _ => 0,
+        })
+        .sum();
+    assert_eq!(turns, game.state.turn);
+}
+
+struct Recorder(Rc<RefCell<Vec<GameEvent>>>);
+
+impl EventSubscriber for Recorder {
+    fn on_event(&mut self, event: &GameEvent) {
+        self.0.borrow_mut().push(event.clone());
+    }
+}
+
+#[test]
+fn journal_and_subscribers_follow_the_event_stream() {
+    let mut game = Game::new(GENERATORS[0], 99);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    game.subscribe(Box::new(Recorder(seen.clone())));
+
+    let events = game.step(GameAction::AutoExplore);
+
+    assert_eq!(*seen.borrow(), events);
+    let journaled = events
+        .iter()
+        .filter(|event| {
+            !matches!(
+                event,
+                GameEvent::TurnAdvanced { .. } | GameEvent::EnemyMoved { .. }
+            )
+        })
+        .count();
+    assert_eq!(game.state.journal.len(), journaled);
+    let last_turn = game.state.journal.last().map(|entry| entry.turn);
+    assert!(last_turn <= Some(game.state.turn));
+}
+
+#[test]
+fn occupancy_tracks_every_enemy() {
+    let mut game = play(4321);
+    game.step(GameAction::Descend);
+
+    let state = &game.state;
+    for (index, enemy) in state.enemies.iter().enumerate() {
+        assert_eq!(state.enemy_at(&enemy.position), Some(index));
+    }
+    let occupied = (0..state.map.height)
+        .flat_map(|y| (0..state.map.width).map(move |x| EntityPosition::new(x, y)))
+        .filter(|pos| state.occupancy.is_occupied(pos))
+        .count();
+    assert_eq!(occupied, state.enemies.len());
+}
+
+#[test]
+fn doors_open_on_bump_and_close_again() {
+    let mut game = Game::new(GENERATORS[0], 99);
+    let start = game.state.player.position.clone();
+    game.state.map.set_tile(start.x + 1, start.y, Tile::Door);
+
+    let events = game.step(GameAction::MovePlayer(Direction::Right));
+    assert!(
+        events
+            .iter()
+            .any(|e| matches!(e, GameEvent::DoorOpened { .. }))
+    );
+    assert_eq!(
+        game.state.player.position, start,
+        "bumping only opens the door"
+    );
+    assert_eq!(game.state.map.tile(start.x + 1, start.y), Tile::OpenDoor);
+
+    let events = game.step(GameAction::CloseDoor(Direction::Right));
+    assert!(
+        events
+            .iter()
+            .any(|e| matches!(e, GameEvent::DoorClosed { .. }))
+    );
+    assert_eq!(game.state.map.tile(start.x + 1, start.y), Tile::Door);
+}
+
+#[test]
+fn terrain_slows_burns_and_swallows_the_hero() {
+    let mut game = Game::new(GENERATORS[0], 99);
+    let start = game.state.player.position.clone();
+    let (x, y) = (start.x + 1, start.y);
+
+    game.state.map.set_tile(x, y, Tile::ShallowWater);
+    game.step(GameAction::MovePlayer(Direction::Right));
+    assert_eq!(game.state.turn, 2, "wading takes two turns");
+    game.step(GameAction::MovePlayer(Direction::Left));
+
+    game.state.map.set_tile(x, y, Tile::Lava);
+    let hp = game.state.player.hp;
+    let events = game.step(GameAction::MovePlayer(Direction::Right));
+    assert!(
+        events
+            .iter()
+            .any(|e| matches!(e, GameEvent::TerrainHurt { .. }))
+    );
+    assert!(game.state.player.hp < hp);
+    game.step(GameAction::MovePlayer(Direction::Left));
+
+    game.state.map.set_tile(x, y, Tile::Chasm);
+    game.step(GameAction::MovePlayer(Direction::Right));
+    assert!(game.state.is_player_dead());
+    assert_eq!(
+        game.state.cause_of_death.as_deref(),
+        Some("Fell into a chasm")
+    );
+}
+
+#[test]
+fn swimmers_cross_deep_water_and_walkers_go_around() {
+    let rows = [
+        "##########",
+        "#........#",
+        "#........#",
+        "#≈≈≈≈≈≈≈.#",
+        "#........#",
+        "#........#",
+        "##########",
+    ];
+    let tiles = rows
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| match c {
+                    '#' => Tile::Wall,
+                    '≈' => Tile::Water,
+                    _ => Tile::Floor,
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut game = Game::new(GENERATORS[0], 99);
+    let state = &mut game.state;
+    state.map = GameMap::from_tiles(tiles);
+    state.fov = FieldOfView::new(state.map.width, state.map.height);
+    state.enemies = vec![
+        Enemy::new(EntityPosition::new(1, 1), "Crocodile", "c", 24, 6, 3, 6)
+            .with_mobility(Mobility::Swimmer),
+        Enemy::new(EntityPosition::new(2, 1), "Goblin", "g", 20, 5, 2, 8),
+    ];
+    state.rebuild_occupancy();
+    state.player.position = EntityPosition::new(1, 5);
+    state.update_fov();
+
+    let mut moves = Vec::new();
+    for _ in 0..6 {
+        for direction in [Direction::Right, Direction::Left] {
+            for event in game.step(GameAction::MovePlayer(direction)) {
+                if let GameEvent::EnemyMoved { name, from, to } = event {
+                    moves.push((name, from, to));
+                }
+            }
+        }
+    }
+
+    let map = &game.state.map;
+    let moves_of = |name: &str| -> Vec<(EntityPosition, EntityPosition)> {
+        moves
+            .iter()
+            .filter(|(mover, ..)| mover == name)
+            .map(|(_, from, to)| (from.clone(), to.clone()))
+            .collect()
+    };
+    let (crocodile, goblin) = (moves_of("Crocodile"), moves_of("Goblin"));
+
+    assert!(
+        crocodile
+            .iter()
+            .any(|(_, to)| map.tile(to.x, to.y) == Tile::Water)
+    );
+    assert!(
+        goblin
+            .iter()
+            .all(|(_, to)| map.tile(to.x, to.y) != Tile::Water)
+    );
+    let gap = EntityPosition::new(8, 3);
+    assert!(
+        goblin.iter().any(|(_, to)| *to == gap),
+        "goes around the lake"
+    );
+}